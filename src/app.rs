@@ -1,34 +1,68 @@
 use std::{path::PathBuf, time::Duration};
 
-use chrono::{SecondsFormat, Utc};
+use skyset::{
+    error::SkysetError,
+    field, history,
+    hooks::HookRunner,
+    io::{self, DiskStamp, ReadOutcome, ReadStatus},
+    model::Skyset,
+    roundtrip,
+    watch::FileWatcher,
+};
 
 use crate::{
     cli::CliOverrides,
-    editor::{self, EditorState, FieldId},
-    io::{self, ReadOutcome},
-    model::{SCHEMA_VERSION, Skyset},
+    conflict::{self, Conflict},
+    editor::{self, EditorState},
+    history_pane::HistoryPane,
+    picker::ColorPicker,
+    undo::UndoStack,
 };
 
 pub struct App {
     path: PathBuf,
     skyset: Skyset,
     editor: EditorState,
+    picker: Option<ColorPicker>,
+    /// The values when the picker opened, restored exactly on cancel.
+    picker_before: Option<Skyset>,
+    history_pane: Option<HistoryPane>,
+    undo_stack: UndoStack,
     last_raw: String,
+    on_disk: Skyset,
+    last_stamp: Option<DiskStamp>,
+    conflict: Option<Conflict>,
     last_poll: std::time::Instant,
     poll_interval: Duration,
+    watcher: FileWatcher,
+    read_status: ReadStatus,
+    save_error: Option<SkysetError>,
+    hooks: HookRunner,
 }
 
 impl App {
     pub fn new(path: PathBuf) -> Self {
-        let outcome = io::load_initial_state(&path);
+        let (outcome, read_status) = io::load_initial_state(&path);
         let editor = EditorState::new(&outcome.skyset);
+        let watcher = FileWatcher::new(&path);
         Self {
             path,
+            on_disk: outcome.skyset.clone(),
+            last_stamp: outcome.stamp,
+            conflict: None,
             skyset: outcome.skyset,
             editor,
+            picker: None,
+            picker_before: None,
+            history_pane: None,
+            undo_stack: UndoStack::default(),
             last_raw: outcome.raw,
             last_poll: std::time::Instant::now(),
             poll_interval: Duration::from_secs(30),
+            watcher,
+            read_status,
+            save_error: None,
+            hooks: HookRunner::new(),
         }
     }
 
@@ -44,6 +78,34 @@ impl App {
         &self.editor
     }
 
+    pub fn picker(&self) -> Option<&ColorPicker> {
+        self.picker.as_ref()
+    }
+
+    pub fn history_pane(&self) -> Option<&HistoryPane> {
+        self.history_pane.as_ref()
+    }
+
+    pub fn conflict(&self) -> Option<&Conflict> {
+        self.conflict.as_ref()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.skyset != self.on_disk
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_native()
+    }
+
+    pub fn read_status(&self) -> &ReadStatus {
+        &self.read_status
+    }
+
+    pub fn save_error(&self) -> Option<&SkysetError> {
+        self.save_error.as_ref()
+    }
+
     pub fn poll_interval(&self) -> Duration {
         self.poll_interval
     }
@@ -52,22 +114,87 @@ impl App {
         self.last_poll.elapsed()
     }
 
+    pub fn watch_timeout(&self) -> Duration {
+        self.watcher.next_check()
+    }
+
+    pub fn check_for_changes(&mut self) {
+        if self.watcher.poll() {
+            self.reload_from_disk();
+        }
+    }
+
     pub fn apply_outcome(&mut self, outcome: ReadOutcome) {
-        if outcome.raw != self.last_raw {
-            self.skyset = outcome.skyset;
-            self.sync_editor_input();
+        if outcome.raw == self.last_raw {
+            return;
+        }
+        if outcome.skyset == self.on_disk {
             self.last_raw = outcome.raw;
+            self.last_stamp = outcome.stamp;
+            return;
         }
+        // An outside change cancels an unfinished pick, so the picker never
+        // puts back values from before the reload.
+        self.close_picker(false);
+        if self.is_dirty() {
+            // Nothing the conflict screen could list: keep the edits on top
+            // of the new version.
+            if !conflict::fields_differ(&self.on_disk, &outcome.skyset)
+                || !conflict::fields_differ(&self.skyset, &outcome.skyset)
+            {
+                self.skyset = conflict::rebase(&self.skyset, &outcome.skyset);
+                self.on_disk = outcome.skyset;
+                self.last_raw = outcome.raw;
+                self.last_stamp = outcome.stamp;
+                return;
+            }
+            let already_seen = self
+                .conflict
+                .as_ref()
+                .is_some_and(|conflict| conflict.theirs_raw() == outcome.raw);
+            if !already_seen {
+                self.conflict = Some(Conflict::new(
+                    &self.skyset,
+                    outcome.skyset,
+                    outcome.raw,
+                    outcome.stamp,
+                ));
+            }
+            return;
+        }
+
+        let before = self.skyset.clone();
+        self.on_disk = outcome.skyset.clone();
+        self.skyset = outcome.skyset;
+        self.record_undo(before);
+        self.sync_editor_input();
+        self.last_raw = outcome.raw;
+        self.last_stamp = outcome.stamp;
     }
 
     pub fn reload_from_disk(&mut self) {
         self.last_poll = std::time::Instant::now();
         match io::read_skyset(&self.path) {
-            Ok(outcome) => self.apply_outcome(outcome),
-            Err(_) => {
-                self.skyset = Skyset::default();
+            Ok(outcome) => {
+                self.read_status = io::status_for(&outcome);
+                self.apply_outcome(outcome);
+            }
+            Err(SkysetError::NotFound(_)) => {
+                self.read_status = ReadStatus::Missing;
+                self.close_picker(false);
+                // Unsaved edits stay on screen (and are saved to a new file
+                // with `s`); only a clean editor falls back to the defaults.
+                if !self.is_dirty() {
+                    let before = std::mem::take(&mut self.skyset);
+                    self.record_undo(before);
+                    self.sync_editor_input();
+                }
+                self.on_disk = Skyset::default();
                 self.last_raw = String::new();
+                self.last_stamp = None;
             }
+            // Keep whatever is on screen; a typo on disk should not look like a reset.
+            Err(err) => self.read_status = ReadStatus::Failed(err),
         }
     }
 
@@ -92,79 +219,253 @@ impl App {
     }
 
     pub fn apply_editor_input(&mut self) {
+        let before = self.skyset.clone();
         let field = self.editor.current_field();
         if field.is_toggle() {
-            editor::toggle_field(&mut self.skyset, field);
-            self.sync_editor_input();
+            field::toggle_field(&mut self.skyset, field);
+        } else {
+            let value = self.editor.input().trim().to_string();
+            let _ = field::set_field_value(&mut self.skyset, field, value);
+        }
+        self.record_undo(before);
+        self.sync_editor_input();
+    }
+
+    pub fn open_history(&mut self) {
+        match history::list(&self.path) {
+            Ok(entries) => self.history_pane = Some(HistoryPane::new(entries)),
+            Err(err) => self.read_status = ReadStatus::Failed(err),
+        }
+    }
+
+    pub fn history_next(&mut self) {
+        if let Some(pane) = &mut self.history_pane {
+            pane.next();
+        }
+    }
+
+    pub fn history_previous(&mut self) {
+        if let Some(pane) = &mut self.history_pane {
+            pane.previous();
+        }
+    }
+
+    pub fn close_history(&mut self) {
+        self.history_pane = None;
+    }
+
+    /// Loads the selected archived version into the editor as an unsaved
+    /// (and undoable) change; `s` writes it.
+    pub fn restore_from_history(&mut self) {
+        let Some(restored) = self
+            .history_pane
+            .take()
+            .and_then(|pane| pane.current().cloned())
+        else {
             return;
+        };
+        let before = std::mem::replace(&mut self.skyset, restored);
+        self.record_undo(before);
+        self.sync_editor_input();
+    }
+
+    pub fn open_picker(&mut self) {
+        let field = self.editor.current_field();
+        if field.is_color() {
+            let value = field::field_value(&self.skyset, field);
+            self.picker = Some(ColorPicker::open(field, &value));
+            self.picker_before = Some(self.skyset.clone());
         }
+    }
 
-        let value = self.editor.input().trim().to_string();
-        let _ = editor::set_field_value(&mut self.skyset, field, value);
+    pub fn picker_next_channel(&mut self) {
+        if let Some(picker) = &mut self.picker {
+            picker.next_channel();
+        }
+    }
+
+    pub fn picker_previous_channel(&mut self) {
+        if let Some(picker) = &mut self.picker {
+            picker.previous_channel();
+        }
+    }
+
+    pub fn picker_adjust(&mut self, delta: i32) {
+        let Some(picker) = &mut self.picker else {
+            return;
+        };
+        picker.adjust(delta);
+        let _ = field::set_field_value(&mut self.skyset, picker.field(), picker.hex());
+        self.sync_editor_input();
+    }
+
+    /// Keeps the picked color, or puts back the values from when the picker
+    /// opened (and with them the dirty state), even if the original field
+    /// was empty or not a valid color.
+    pub fn close_picker(&mut self, keep: bool) {
+        if self.picker.take().is_none() {
+            return;
+        }
+        let Some(original) = self.picker_before.take() else {
+            return;
+        };
+        if keep {
+            self.record_undo(original);
+        } else {
+            self.skyset = original;
+        }
         self.sync_editor_input();
     }
 
     pub fn save(&mut self) {
-        self.skyset.updated_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
-        self.skyset.version = SCHEMA_VERSION;
+        if self.conflict.is_some() {
+            return;
+        }
+        if io::current_stamp(&self.path) != self.last_stamp
+            && let Ok(outcome) = io::read_skyset(&self.path)
+            && outcome.skyset != self.on_disk
+        {
+            self.conflict = Some(Conflict::new(
+                &self.skyset,
+                outcome.skyset,
+                outcome.raw,
+                outcome.stamp,
+            ));
+            return;
+        }
 
-        let serialized = match serde_yaml::to_string(&self.skyset) {
+        io::prepare_for_save(&mut self.skyset);
+
+        let serialized = match roundtrip::render(&self.last_raw, &self.skyset) {
             Ok(value) => value,
-            Err(_) => return,
+            Err(err) => {
+                self.save_error = Some(err);
+                return;
+            }
         };
 
-        if io::write_skyset(&self.path, &serialized).is_err() {
+        if let Err(err) = io::write_skyset(&self.path, &serialized) {
+            self.save_error = Some(err);
             return;
         }
 
+        self.save_error = None;
+        self.read_status = ReadStatus::Loaded;
+        self.on_disk = self.skyset.clone();
+        self.last_stamp = io::current_stamp(&self.path);
         self.last_raw = serialized;
+
+        // Hooks may take seconds; keep the editor responsive.
+        self.hooks.on_save(&self.path, &self.skyset);
+    }
+
+    /// Waits for the hooks of earlier saves, so quitting does not kill them.
+    pub fn finish_hooks(&mut self) {
+        self.hooks.finish();
+    }
+
+    pub fn keep_mine(&mut self) {
+        if let Some(conflict) = self.conflict.take() {
+            self.adopt_baseline(conflict);
+        }
+    }
+
+    pub fn take_theirs(&mut self) {
+        if let Some(conflict) = self.conflict.take() {
+            let before = std::mem::replace(&mut self.skyset, conflict.theirs().clone());
+            self.record_undo(before);
+            self.adopt_baseline(conflict);
+        }
+    }
+
+    pub fn apply_merge(&mut self) {
+        if let Some(conflict) = self.conflict.take() {
+            let merged = conflict.merged(&self.skyset);
+            let before = std::mem::replace(&mut self.skyset, merged);
+            self.record_undo(before);
+            self.adopt_baseline(conflict);
+        }
+    }
+
+    pub fn set_merging(&mut self, merging: bool) {
+        if let Some(conflict) = &mut self.conflict {
+            conflict.set_merging(merging);
+        }
+    }
+
+    pub fn conflict_next(&mut self) {
+        if let Some(conflict) = &mut self.conflict {
+            conflict.next();
+        }
+    }
+
+    pub fn conflict_previous(&mut self) {
+        if let Some(conflict) = &mut self.conflict {
+            conflict.previous();
+        }
+    }
+
+    pub fn conflict_toggle(&mut self) {
+        if let Some(conflict) = &mut self.conflict {
+            conflict.toggle();
+        }
+    }
+
+    /// Treats the version that caused the conflict as the new on-disk
+    /// baseline, so the next save goes through.
+    fn adopt_baseline(&mut self, conflict: Conflict) {
+        let (theirs, raw, stamp) = conflict.into_theirs();
+        self.on_disk = theirs;
+        self.last_raw = raw;
+        self.last_stamp = stamp;
+        self.sync_editor_input();
     }
 
     pub fn reset(&mut self) {
-        self.skyset = Skyset::default();
+        let before = std::mem::take(&mut self.skyset);
+        self.record_undo(before);
         self.sync_editor_input();
     }
 
+    pub fn undo(&mut self) {
+        if let Some(previous) = self.undo_stack.undo(&self.skyset) {
+            self.skyset = previous;
+            self.sync_editor_input();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(next) = self.undo_stack.redo(&self.skyset) {
+            self.skyset = next;
+            self.sync_editor_input();
+        }
+    }
+
+    pub fn undo_stack(&self) -> &UndoStack {
+        &self.undo_stack
+    }
+
+    /// Applies override flags to the in-memory values. `parse_cli` has
+    /// already rejected invalid ones unless `--lenient` was given, in which
+    /// case they are skipped here.
     pub fn apply_overrides(&mut self, overrides: &CliOverrides) {
-        set_string_override(&mut self.skyset.origin, &overrides.origin);
-        set_string_override(&mut self.skyset.message, &overrides.message);
-        set_string_override(&mut self.skyset.submessage, &overrides.submessage);
-
-        for (field, value) in [
-            (FieldId::ThemeMode, &overrides.theme_mode),
-            (FieldId::Accent, &overrides.accent),
-            (FieldId::Primary, &overrides.primary),
-            (FieldId::Secondary, &overrides.secondary),
-            (FieldId::Tertiary, &overrides.tertiary),
-            (FieldId::Background1, &overrides.background1),
-            (FieldId::Background2, &overrides.background2),
-            (FieldId::Background3, &overrides.background3),
-            (FieldId::Hero1, &overrides.hero1),
-            (FieldId::Hero2, &overrides.hero2),
-            (FieldId::SourceWillUpdate, &overrides.source_will_update),
-        ] {
-            apply_override(&mut self.skyset, field, value);
+        for (field, value) in overrides.assignments() {
+            let _ = field::set_field_value(&mut self.skyset, field, value);
         }
 
         self.sync_editor_input();
     }
 
+    fn record_undo(&mut self, before: Skyset) {
+        if before != self.skyset {
+            self.undo_stack.record(before);
+        }
+    }
+
     fn sync_editor_input(&mut self) {
-        self.editor.set_input(editor::field_value(
+        self.editor.set_input(field::field_value(
             &self.skyset,
             self.editor.current_field(),
         ));
     }
 }
-
-fn apply_override(skyset: &mut Skyset, field: FieldId, value: &Option<String>) {
-    if let Some(value) = value {
-        let _ = editor::set_field_value(skyset, field, value.clone());
-    }
-}
-
-fn set_string_override(target: &mut String, value: &Option<String>) {
-    if let Some(value) = value {
-        *target = value.clone();
-    }
-}
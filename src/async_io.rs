@@ -0,0 +1,106 @@
+//! Async (tokio) counterparts of the `io` and `watch` APIs, behind the
+//! `tokio` feature.
+//!
+//! ```no_run
+//! use tokio_stream::StreamExt;
+//!
+//! # async fn demo() {
+//! let path = skyset::paths::default_config_path();
+//! let mut updates = Box::pin(skyset::async_io::watch(&path));
+//! while let Some(update) = updates.next().await {
+//!     match update {
+//!         Ok(skyset) => println!("accent is now {}", skyset.theme.accent),
+//!         Err(err) => eprintln!("{err}"),
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::path::Path;
+
+use tokio::{fs, sync::mpsc, task};
+use tokio_stream::{Stream, wrappers::ReceiverStream};
+
+use crate::{
+    error::SkysetError,
+    history,
+    io::{self, temp_path},
+    model::Skyset,
+    roundtrip,
+    watch::Watcher,
+};
+
+const CHANNEL_CAPACITY: usize = 16;
+
+pub async fn load(path: impl AsRef<Path>) -> Result<Skyset, SkysetError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|err| SkysetError::from_io(path.to_path_buf(), err))?;
+    io::parse_skyset(path, &content)
+}
+
+/// Async version of `io::save_skyset`: stamps `skyset`, keeps unknown keys
+/// and comments from the current file, and replaces it atomically.
+pub async fn save(path: impl AsRef<Path>, skyset: &mut Skyset) -> Result<String, SkysetError> {
+    let path = path.as_ref();
+    io::prepare_for_save(skyset);
+    let previous = fs::read_to_string(path).await.unwrap_or_default();
+    let serialized = roundtrip::render(&previous, skyset)?;
+    write(path, &serialized).await?;
+    Ok(serialized)
+}
+
+/// Async version of `io::write_skyset`: archives the version being
+/// replaced, writes to a temp file next to `path`, then renames it into
+/// place.
+pub async fn write(path: impl AsRef<Path>, contents: &str) -> Result<(), SkysetError> {
+    let path = path.as_ref();
+    let io_error = |err| SkysetError::from_io(path.to_path_buf(), err);
+    if let Ok(previous) = fs::read_to_string(path).await
+        && previous != contents
+    {
+        let config_path = path.to_path_buf();
+        // Failures are logged by `archive_or_log`; a panic there must not
+        // stop the write either.
+        let _ =
+            task::spawn_blocking(move || history::archive_or_log(&config_path, &previous)).await;
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(io_error)?;
+    }
+
+    let tmp_path = temp_path(path);
+    fs::write(&tmp_path, contents).await.map_err(io_error)?;
+    fs::rename(&tmp_path, path).await.map_err(io_error)?;
+    Ok(())
+}
+
+/// Yields the current `Skyset` right away, then a new one every time the
+/// file settles on different values.
+///
+/// The blocking `watch::Watcher` runs on its own thread and stops once the
+/// stream is dropped.
+pub fn watch(path: impl AsRef<Path>) -> impl Stream<Item = Result<Skyset, SkysetError>> {
+    let path = path.as_ref().to_path_buf();
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    std::thread::spawn(move || {
+        let mut watcher = Watcher::new(&path);
+        if tx.blocking_send(io::load_skyset(&path)).is_err() {
+            return;
+        }
+        while !tx.is_closed() {
+            match watcher.try_next() {
+                Some(result) => {
+                    if tx.blocking_send(result.map(|change| change.new)).is_err() {
+                        return;
+                    }
+                }
+                None => std::thread::sleep(watcher.next_check()),
+            }
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
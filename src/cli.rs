@@ -1,5 +1,15 @@
 use std::path::PathBuf;
 
+use skyset::{
+    export::Registry,
+    field::{self, FieldId},
+    model::Skyset,
+    query::Format,
+    serve::DEFAULT_PORT,
+};
+
+use crate::output::WatchFormat;
+
 #[derive(Default)]
 pub struct CliOverrides {
     pub origin: Option<String>,
@@ -18,29 +28,173 @@ pub struct CliOverrides {
     pub source_will_update: Option<String>,
 }
 
+impl CliOverrides {
+    /// The overrides that were given, in editor field order.
+    pub fn assignments(&self) -> Vec<(FieldId, String)> {
+        [
+            (FieldId::Origin, &self.origin),
+            (FieldId::Message, &self.message),
+            (FieldId::Submessage, &self.submessage),
+            (FieldId::ThemeMode, &self.theme_mode),
+            (FieldId::Accent, &self.accent),
+            (FieldId::Primary, &self.primary),
+            (FieldId::Secondary, &self.secondary),
+            (FieldId::Tertiary, &self.tertiary),
+            (FieldId::Background1, &self.background1),
+            (FieldId::Background2, &self.background2),
+            (FieldId::Background3, &self.background3),
+            (FieldId::Hero1, &self.hero1),
+            (FieldId::Hero2, &self.hero2),
+            (FieldId::SourceWillUpdate, &self.source_will_update),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| value.clone().map(|value| (field, value)))
+        .collect()
+    }
+}
+
+type OverrideSetter = fn(&mut CliOverrides, String);
+
 pub enum OutputMode {
     Tui,
     Oneline,
     Json,
 }
 
+/// Exit statuses shared by every subcommand, so shell scripts can branch on them.
+pub const EXIT_OK: u8 = 0;
+/// The command ran, but the answer is "no": invalid file, files differ, path not found.
+pub const EXIT_NEGATIVE: u8 = 1;
+pub const EXIT_USAGE: u8 = 2;
+/// A file could not be read, parsed or written.
+pub const EXIT_IO: u8 = 3;
+
+pub enum Command {
+    Tui,
+    Get {
+        path: String,
+        format: Format,
+        /// Printed instead of failing when the file or value is missing.
+        fallback: Option<String>,
+    },
+    Set {
+        assignments: Vec<(FieldId, String)>,
+        /// Merge a YAML/JSON fragment from stdin before the assignments.
+        from_stdin: bool,
+    },
+    Validate {
+        file: Option<PathBuf>,
+    },
+    /// Render the theme with the named exporter; without a name, list them.
+    Export {
+        name: Option<String>,
+        output: Option<PathBuf>,
+    },
+    Watch {
+        format: WatchFormat,
+    },
+    /// Publish changes over `skyset.sock` until killed.
+    Daemon,
+    History(HistoryAction),
+    /// Serve the theme over HTTP on `host:port`; `cors` may read responses.
+    Serve {
+        host: String,
+        port: u16,
+        cors: Option<String>,
+    },
+    /// Run the hooks once (`hooks run`) or on every change (`hooks watch`).
+    Hooks {
+        watch: bool,
+    },
+    /// Compare two files; `all` includes `updated_at` and `_version`.
+    Diff {
+        left: PathBuf,
+        right: PathBuf,
+        all: bool,
+    },
+    /// Recolor this terminal and the ttys in `terminal.yml`, and refresh
+    /// the `sequences` file.
+    ApplyTerminal,
+}
+
+const SUBCOMMANDS: [&str; 11] = [
+    "get",
+    "set",
+    "validate",
+    "export",
+    "watch",
+    "diff",
+    "hooks",
+    "daemon",
+    "serve",
+    "history",
+    "apply-terminal",
+];
+
+pub enum HistoryAction {
+    List,
+    Show(String),
+    Restore(String),
+}
+
 pub struct CliArgs {
     pub config_path: Option<PathBuf>,
     pub show_help: bool,
     pub overrides: CliOverrides,
     pub output_mode: OutputMode,
+    pub command: Command,
+    /// Print what `set`/`--write` would save instead of writing it.
+    pub dry_run: bool,
 }
 
-pub fn parse_cli<I>(args: I) -> CliArgs
+/// Flags that take a value, so a bare `--accent` at the end can be reported
+/// as missing its value rather than unknown.
+const VALUE_FLAGS: [&str; 22] = [
+    "--config-file",
+    "--output",
+    "-o",
+    "--format",
+    "--fallback",
+    "--host",
+    "--port",
+    "--cors",
+    "--message",
+    "--submessage",
+    "--origin",
+    "--mode",
+    "--accent",
+    "--primary",
+    "--secondary",
+    "--tertiary",
+    "--background1",
+    "--background2",
+    "--background3",
+    "--hero1",
+    "--hero2",
+    "--source-will-update",
+];
+
+/// Parses the command line, collecting every bad flag and value so they can
+/// be reported together. With `--lenient`, unknown flags and invalid
+/// override values are dropped instead.
+pub fn parse_cli<I>(args: I) -> Result<CliArgs, Vec<String>>
 where
     I: IntoIterator<Item = String>,
 {
     let mut iter = args.into_iter();
-    let mut positional: Option<PathBuf> = None;
+    let mut positional: Vec<String> = Vec::new();
+    let mut subcommand: Option<String> = None;
+    let mut config_file: Option<PathBuf> = None;
+    let mut flags = CommandFlags::default();
     let mut show_help = false;
     let mut overrides = CliOverrides::default();
     let mut output_mode = OutputMode::Tui;
-    let override_flags: [(&str, fn(&mut CliOverrides, String)); 13] = [
+    let mut write = false;
+    let mut dry_run = false;
+    let mut lenient = false;
+    let mut from_stdin = false;
+    let mut errors: Vec<String> = Vec::new();
+    let override_flags: [(&str, OverrideSetter); 13] = [
         ("--message", set_message),
         ("--submessage", set_submessage),
         ("--origin", set_origin),
@@ -69,13 +223,55 @@ where
             output_mode = OutputMode::Json;
             continue;
         }
+        if arg == "--write" {
+            write = true;
+            continue;
+        }
+        if arg == "--dry-run" {
+            dry_run = true;
+            continue;
+        }
+        if arg == "-" || arg == "--from-stdin" {
+            from_stdin = true;
+            continue;
+        }
+        if arg == "--lenient" {
+            lenient = true;
+            continue;
+        }
+        if arg == "--all" {
+            flags.all = true;
+            continue;
+        }
         if let Some(value) = take_value(&arg, "--config-file", &mut iter) {
-            return CliArgs {
-                config_path: Some(PathBuf::from(value)),
-                show_help,
-                overrides,
-                output_mode,
-            };
+            config_file = Some(PathBuf::from(value));
+            continue;
+        }
+        if let Some(value) =
+            take_value(&arg, "--output", &mut iter).or_else(|| take_value(&arg, "-o", &mut iter))
+        {
+            flags.output = Some(PathBuf::from(value));
+            continue;
+        }
+        if let Some(value) = take_value(&arg, "--format", &mut iter) {
+            flags.format = Some(value);
+            continue;
+        }
+        if let Some(value) = take_value(&arg, "--fallback", &mut iter) {
+            flags.fallback = Some(value);
+            continue;
+        }
+        if let Some(value) = take_value(&arg, "--host", &mut iter) {
+            flags.host = Some(value);
+            continue;
+        }
+        if let Some(value) = take_value(&arg, "--port", &mut iter) {
+            flags.port = Some(value);
+            continue;
+        }
+        if let Some(value) = take_value(&arg, "--cors", &mut iter) {
+            flags.cors = Some(value);
+            continue;
         }
         let mut matched_override = false;
         for (flag, setter) in override_flags {
@@ -92,16 +288,190 @@ where
             overrides.source_will_update = Some(value);
             continue;
         }
-        if positional.is_none() && !arg.starts_with('-') {
-            positional = Some(PathBuf::from(arg));
+        if arg.starts_with('-') {
+            if VALUE_FLAGS.contains(&arg.as_str()) {
+                errors.push(format!("{arg} needs a value"));
+            } else {
+                errors.push(format!("unknown flag {arg}; see --help"));
+            }
+            continue;
+        }
+        if subcommand.is_none() && positional.is_empty() && SUBCOMMANDS.contains(&arg.as_str()) {
+            subcommand = Some(arg);
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    let (mut command, config_path) = match subcommand.as_deref() {
+        None => {
+            if positional.len() > 1 {
+                return Err(vec![format!("unexpected argument: {}", positional[1])]);
+            }
+            (
+                Command::Tui,
+                config_file.or(positional.pop().map(PathBuf::from)),
+            )
+        }
+        Some(name) => (
+            build_command(name, positional, flags).map_err(|err| vec![err])?,
+            config_file,
+        ),
+    };
+
+    // `--write`, `--dry-run` and stdin input turn the override flags into a
+    // headless `set`.
+    if matches!(command, Command::Tui) && (write || dry_run || from_stdin) {
+        command = Command::Set {
+            assignments: Vec::new(),
+            from_stdin,
+        };
+    }
+    if let Command::Set { assignments, .. } = &command {
+        for (field, value) in assignments {
+            if let Err(err) = check_value(*field, value) {
+                errors.push(format!("{}={err}", field.name()));
+            }
+        }
+    }
+    for (field, value) in overrides.assignments() {
+        if let Err(err) = check_value(field, &value) {
+            errors.push(format!("--{} {err}", field.name()));
+        }
+    }
+    if let Command::Set {
+        assignments,
+        from_stdin: stdin,
+    } = &mut command
+    {
+        *stdin |= from_stdin;
+        assignments.extend(overrides.assignments());
+        if lenient {
+            assignments.retain(|(field, value)| check_value(*field, value).is_ok());
+        }
+        if assignments.is_empty() && !*stdin && !show_help {
+            errors.push("nothing to set; pass FIELD=VALUE or override flags".to_string());
+            return Err(errors);
         }
     }
+    if !errors.is_empty() && !lenient {
+        return Err(errors);
+    }
 
-    CliArgs {
-        config_path: positional,
+    Ok(CliArgs {
+        config_path,
         show_help,
         overrides,
         output_mode,
+        command,
+        dry_run,
+    })
+}
+
+/// Flags that only mean something to a particular subcommand.
+#[derive(Default)]
+struct CommandFlags {
+    output: Option<PathBuf>,
+    format: Option<String>,
+    fallback: Option<String>,
+    host: Option<String>,
+    port: Option<String>,
+    cors: Option<String>,
+    all: bool,
+}
+
+fn build_command(
+    name: &str,
+    mut positional: Vec<String>,
+    flags: CommandFlags,
+) -> Result<Command, String> {
+    let count = positional.len();
+    let command = match (name, count) {
+        ("get", 1) => Command::Get {
+            path: positional.remove(0),
+            format: flags.format.as_deref().unwrap_or("plain").parse()?,
+            fallback: flags.fallback,
+        },
+        ("set", _) => Command::Set {
+            assignments: positional
+                .into_iter()
+                .map(|item| parse_assignment(&item))
+                .collect::<Result<_, _>>()?,
+            from_stdin: false,
+        },
+        ("validate", 0 | 1) => Command::Validate {
+            file: positional.pop().map(PathBuf::from),
+        },
+        ("export", 0) => Command::Export {
+            name: None,
+            output: None,
+        },
+        ("export", 1) => {
+            let name = positional.remove(0);
+            let registry = Registry::builtin();
+            if registry.get(&name).is_none() {
+                return Err(format!(
+                    "unknown exporter {name:?} (expected one of: {})",
+                    registry.names().join(", ")
+                ));
+            }
+            Command::Export {
+                name: Some(name),
+                output: flags.output,
+            }
+        }
+        ("watch", 0) => Command::Watch {
+            format: flags.format.as_deref().unwrap_or("oneline").parse()?,
+        },
+        ("daemon", 0) => Command::Daemon,
+        ("history", 0) => Command::History(HistoryAction::List),
+        ("history", 1) if positional[0] == "list" => Command::History(HistoryAction::List),
+        ("history", 2) if positional[0] == "show" => {
+            Command::History(HistoryAction::Show(positional.remove(1)))
+        }
+        ("history", 2) if positional[0] == "restore" => {
+            Command::History(HistoryAction::Restore(positional.remove(1)))
+        }
+        ("serve", 0) => Command::Serve {
+            host: flags.host.unwrap_or_else(|| "127.0.0.1".to_string()),
+            port: match flags.port {
+                Some(port) => port
+                    .parse()
+                    .map_err(|_| format!("--port {port}: expected a number from 0 to 65535"))?,
+                None => DEFAULT_PORT,
+            },
+            cors: flags.cors,
+        },
+        ("hooks", 1) if matches!(positional[0].as_str(), "run" | "watch") => Command::Hooks {
+            watch: positional[0] == "watch",
+        },
+        ("apply-terminal", 0) => Command::ApplyTerminal,
+        ("diff", 2) => Command::Diff {
+            right: PathBuf::from(positional.remove(1)),
+            left: PathBuf::from(positional.remove(0)),
+            all: flags.all,
+        },
+        _ => {
+            return Err(format!(
+                "wrong number of arguments for `{name}`; see --help"
+            ));
+        }
+    };
+    Ok(command)
+}
+
+fn check_value(field: FieldId, value: &str) -> Result<(), String> {
+    field::set_field_value(&mut Skyset::default(), field, value.to_string())
+        .map_err(|_| format!("{value}: expected {}", field.expected()))
+}
+
+fn parse_assignment(item: &str) -> Result<(FieldId, String), String> {
+    let Some((key, value)) = item.split_once('=') else {
+        return Err(format!("expected FIELD=VALUE, got {item:?}"));
+    };
+    match FieldId::from_key(key) {
+        Some(field) => Ok((field, value.to_string())),
+        None => Err(format!("unknown field {key:?}")),
     }
 }
 
@@ -170,6 +540,6 @@ fn set_hero2(overrides: &mut CliOverrides, value: String) {
 
 pub fn print_help() {
     println!(
-        "Skyset (TUI prototype)\n\nUSAGE:\n  skyset [PATH]\n  skyset --config-file <PATH>\n\nOPTIONS:\n  --config-file <PATH>       Use an explicit config file\n  --message <TEXT>           Override message text\n  --submessage <TEXT>        Override submessage text\n  --origin <ID>              Override origin identifier\n  --mode <dark|light|system> Override theme mode\n  --accent <HEX>             Override accent color\n  --primary <HEX>            Override palette primary color\n  --secondary <HEX>          Override palette secondary color\n  --tertiary <HEX>           Override palette tertiary color\n  --background1 <HEX>        Override background gradient stop #1\n  --background2 <HEX>        Override background gradient stop #2\n  --background3 <HEX>        Override background gradient stop #3\n  --hero1 <HEX>              Override hero gradient stop #1\n  --hero2 <HEX>              Override hero gradient stop #2\n  --source-will-update <BOOL>Override source_will_update\n  --oneline                  Print a one-line summary and exit\n  --json                     Print JSON status and exit\n  -h, --help                 Show this help message\n\nIf PATH is a directory, skyset will normalize it to latest.yml."
+        "Skyset (TUI prototype)\n\nUSAGE:\n  skyset [PATH]\n  skyset --config-file <PATH>\n  skyset <COMMAND> [ARGS] [--config-file <PATH>]\n\nCOMMANDS:\n  get <PATH>                 Print a value or subtree, e.g. palette.primary, palette\n  set <FIELD=VALUE>... [-]   Write values to the file (stamps updated_at)\n  validate [FILE]            Check a file against the schema\n  export [NAME]              Render the theme with an exporter (-o to write a file);\n                             without NAME, list the exporters\n  watch                      Print a line now and on every change (--format)\n  diff <A> <B> [--all]       Show the fields that differ between two files\n                             (--all includes updated_at and _version)\n  hooks <run|watch>          Run the commands in hooks.yml once, or on every change\n  daemon                     Publish changes to subscribers on skyset.sock\n  history [list]             List archived versions, newest first\n  history show <ID|N>        Print an archived version\n  history restore <ID|N>     Write an archived version back (stamps updated_at)\n  serve                      Serve /theme.json, /theme.css and /events (SSE) over HTTP\n  apply-terminal             Recolor this terminal and the ttys in terminal.yml (OSC 4/10/11/12)\n\nOPTIONS:\n  --config-file <PATH>       Use an explicit config file\n  --message <TEXT>           Override message text\n  --submessage <TEXT>        Override submessage text\n  --origin <ID>              Override origin identifier\n  --mode <dark|light|system> Override theme mode\n  --accent <HEX>             Override accent color\n  --primary <HEX>            Override palette primary color\n  --secondary <HEX>          Override palette secondary color\n  --tertiary <HEX>           Override palette tertiary color\n  --background1 <HEX>        Override background gradient stop #1\n  --background2 <HEX>        Override background gradient stop #2\n  --background3 <HEX>        Override background gradient stop #3\n  --hero1 <HEX>              Override hero gradient stop #1\n  --hero2 <HEX>              Override hero gradient stop #2\n  --source-will-update <BOOL>Override source_will_update\n  --write                    Apply the override flags to the file and exit\n  -, --from-stdin            Merge a YAML/JSON fragment from stdin (null deletes a key)\n  --dry-run                  With set/--write: print the YAML instead of writing\n  --lenient                  Ignore unknown flags and invalid values instead of failing\n  -o, --output <PATH>        Write `export` output to a file\n  --format <FORMAT>          get: plain, hex (RRGGBB), rgb (r,g,b) or 0x (0xRRGGBB)\n                             watch: oneline, jsonl, or a template like '{{theme.accent:hex}} {{message}}'\n  --fallback <VALUE>         get: print VALUE if the file or value is missing\n  --port <PORT>              serve: port to listen on (default 7477)\n  --host <ADDR>              serve: address to bind (default 127.0.0.1)\n  --cors <ORIGIN>            serve: let pages from ORIGIN read the responses\n  --oneline                  Print a one-line summary and exit\n  --json                     Print JSON status and exit\n  -h, --help                 Show this help message\n\nFIELD and PATH are dotted paths (theme.accent, gradients.hero[1]) or a flag name (accent, hero2).\n\nEXIT STATUS:\n  0  success\n  1  negative result: invalid file, files differ, field not found\n  2  usage error\n  3  file could not be read, parsed or written\n\nIf PATH is a directory, skyset will normalize it to latest.yml."
     );
 }
@@ -1,10 +1,142 @@
+/// Parses `#RRGGBB`, `RRGGBB` or the `#RGB` shorthand. This is the one
+/// definition of a color: validation, the editor and every exporter use it.
 pub fn parse_hex_rgb(value: &str) -> Option<(u8, u8, u8)> {
-    let trimmed = value.trim().trim_start_matches('#');
-    if trimmed.len() != 6 || !trimmed.chars().all(|c| c.is_ascii_hexdigit()) {
+    let digits = value.trim().trim_start_matches('#');
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
         return None;
     }
-    let r = u8::from_str_radix(&trimmed[0..2], 16).ok()?;
-    let g = u8::from_str_radix(&trimmed[2..4], 16).ok()?;
-    let b = u8::from_str_radix(&trimmed[4..6], 16).ok()?;
-    Some((r, g, b))
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match digits.len() {
+        3 => {
+            let short = |idx: usize| channel(&digits[idx..=idx]).map(|value| value * 17);
+            Some((short(0)?, short(1)?, short(2)?))
+        }
+        6 => Some((
+            channel(&digits[0..2])?,
+            channel(&digits[2..4])?,
+            channel(&digits[4..6])?,
+        )),
+        _ => None,
+    }
+}
+
+pub fn format_hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+/// Converts RGB to HSL with hue in degrees (0..360) and saturation and
+/// lightness in 0..1.
+pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let r = f64::from(r) / 255.0;
+    let g = f64::from(g) / 255.0;
+    let b = f64::from(b) / 255.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation, lightness)
+}
+
+pub fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// WCAG relative luminance, from 0 (black) to 1 (white).
+pub fn relative_luminance(r: u8, g: u8, b: u8) -> f64 {
+    let linear = |channel: u8| {
+        let value = f64::from(channel) / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG contrast ratio between two colors, from 1 to 21.
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let a = relative_luminance(a.0, a.1, a.2);
+    let b = relative_luminance(b.0, b.1, b.2);
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Linear blend from `a` (amount 0) to `b` (amount 1).
+pub fn mix(a: (u8, u8, u8), b: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    let channel = |a: u8, b: u8| {
+        (f64::from(a) + (f64::from(b) - f64::from(a)) * amount)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    };
+    (channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        export::{Css, Exporter},
+        model::Skyset,
+        query::{self, Format},
+        validate::validate,
+    };
+
+    #[test]
+    fn hsl_round_trip() {
+        for (r, g, b) in [
+            (0x7C, 0x4D, 0xFF),
+            (0x0E, 0x0E, 0x10),
+            (255, 0, 0),
+            (12, 200, 99),
+        ] {
+            let (h, s, l) = rgb_to_hsl(r, g, b);
+            assert_eq!(hsl_to_rgb(h, s, l), (r, g, b));
+        }
+    }
+
+    #[test]
+    fn shorthand_is_a_color_everywhere() {
+        assert_eq!(parse_hex_rgb("#fa0"), Some((0xFF, 0xAA, 0x00)));
+        assert_eq!(parse_hex_rgb("#ffff"), None);
+
+        let mut skyset = Skyset::default();
+        skyset.theme.accent = "#fff".to_string();
+        assert!(
+            validate(&skyset)
+                .iter()
+                .all(|issue| issue.path != "theme.accent")
+        );
+        assert!(
+            Css.render(&skyset)
+                .unwrap()
+                .contains("--skyset-accent: #FFFFFF;")
+        );
+        let accent = query::lookup(&skyset, "theme.accent").unwrap();
+        assert_eq!(query::render(&accent, Format::Hex).unwrap(), "FFFFFF");
+    }
 }
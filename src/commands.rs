@@ -0,0 +1,438 @@
+use std::{
+    io::{ErrorKind, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde_json::Value;
+use skyset::{
+    diff::{self, FieldChange},
+    error::SkysetError,
+    export::Registry,
+    field::{self, FieldId},
+    history,
+    hooks::{HooksConfig, hooks_path},
+    io, merge,
+    model::Skyset,
+    query::{self, Format},
+    roundtrip,
+    serve::Server,
+    terminal::{self, TerminalConfig, terminal_config_path},
+    validate::validate,
+    watch::Watcher,
+};
+
+use crate::{
+    cli::{Command, EXIT_IO, EXIT_NEGATIVE, EXIT_OK, EXIT_USAGE, HistoryAction},
+    output::{WatchFormat, swatches},
+};
+
+pub fn run_command(command: Command, path: PathBuf, dry_run: bool) -> ExitCode {
+    let code = match command {
+        Command::Tui => EXIT_OK,
+        Command::Get {
+            path: key,
+            format,
+            fallback,
+        } => get(&path, &key, format, fallback.as_deref()),
+        Command::Set {
+            assignments,
+            from_stdin,
+        } => set(&path, &assignments, from_stdin, dry_run),
+        Command::Validate { file } => validate_file(file.as_deref().unwrap_or(&path)),
+        Command::Export { name, output } => export(&path, name.as_deref(), output.as_deref()),
+        Command::Watch { format } => watch(&path, &format),
+        Command::Daemon => daemon(&path),
+        Command::History(action) => history_command(&path, action),
+        Command::Serve { host, port, cors } => serve(&path, &host, port, cors),
+        Command::Hooks { watch } => run_hooks(&path, watch),
+        Command::Diff { left, right, all } => diff_files(&left, &right, all),
+        Command::ApplyTerminal => apply_terminal(&path),
+    };
+    ExitCode::from(code)
+}
+
+fn get(path: &Path, key: &str, format: Format, fallback: Option<&str>) -> u8 {
+    let key = FieldId::from_key(key).map_or(key, |field| field.path());
+    let value = match io::load_skyset(path) {
+        Ok(skyset) => query::lookup(&skyset, key),
+        Err(err) => {
+            if fallback.is_none() {
+                return report(err);
+            }
+            if !matches!(err, SkysetError::NotFound(_)) {
+                eprintln!("skyset: {err}");
+            }
+            None
+        }
+    };
+    let rendered = match (value, fallback) {
+        (Some(value), _) => query::render(&value, format),
+        (None, Some(fallback)) => Ok(query::render(&Value::String(fallback.to_string()), format)
+            .unwrap_or_else(|_| fallback.to_string())),
+        (None, None) => Err(format!("no value at {key}")),
+    };
+    match rendered {
+        Ok(text) => {
+            println!("{text}");
+            EXIT_OK
+        }
+        Err(err) => {
+            eprintln!("skyset: {err}");
+            EXIT_NEGATIVE
+        }
+    }
+}
+
+/// Applies a stdin patch and `assignments` to the file without the TUI, then stamps, validates
+/// and writes it the same way `App::save` does.
+fn set(path: &Path, assignments: &[(FieldId, String)], from_stdin: bool, dry_run: bool) -> u8 {
+    let (before, raw) = match io::read_skyset(path) {
+        Ok(outcome) => (outcome.skyset, outcome.raw),
+        Err(SkysetError::NotFound(_)) => (Skyset::default(), String::new()),
+        Err(err) => return report(err),
+    };
+
+    let mut skyset = before.clone();
+    if from_stdin {
+        match read_stdin_patch()
+            .and_then(|patch| merge::apply_patch(Path::new(STDIN), &skyset, &patch))
+        {
+            Ok(merged) => skyset = merged,
+            Err(err) => return report(err),
+        }
+    }
+    for (field, value) in assignments {
+        if let Err(err) = field::set_field_value(&mut skyset, *field, value.clone()) {
+            eprintln!("skyset: {}: {err}", field.path());
+            return EXIT_USAGE;
+        }
+    }
+    io::prepare_for_save(&mut skyset);
+
+    let issues = validate(&skyset);
+    if !issues.is_empty() {
+        for issue in &issues {
+            eprintln!("skyset: {}: {}", issue.path, issue.message);
+        }
+        return EXIT_NEGATIVE;
+    }
+
+    let contents = match roundtrip::render(&raw, &skyset) {
+        Ok(contents) => contents,
+        Err(err) => return report(err),
+    };
+    if dry_run {
+        print!("{contents}");
+    } else if let Err(err) = io::write_skyset(path, &contents) {
+        return report(err);
+    }
+
+    // Every save stamps `updated_at`; only the values the user touched matter.
+    for change in diff::diff(&before, &skyset)
+        .iter()
+        .filter(|change| !change.is_bookkeeping())
+    {
+        eprintln!("{}", describe(change));
+    }
+    EXIT_OK
+}
+
+/// How errors in a patch read from stdin name their source.
+const STDIN: &str = "<stdin>";
+
+fn read_stdin_patch() -> Result<Value, SkysetError> {
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|err| SkysetError::from_io(STDIN.into(), err))?;
+    merge::parse_patch(Path::new(STDIN), &text)
+}
+
+fn validate_file(path: &Path) -> u8 {
+    let skyset = match io::load_skyset(path) {
+        Ok(skyset) => skyset,
+        Err(err @ SkysetError::Parse { .. }) => {
+            eprintln!("{err}");
+            return EXIT_NEGATIVE;
+        }
+        Err(err) => return report(err),
+    };
+
+    let issues = validate(&skyset);
+    for issue in &issues {
+        eprintln!("{}: {}: {}", path.display(), issue.path, issue.message);
+    }
+    if issues.is_empty() {
+        println!("{}: ok", path.display());
+        EXIT_OK
+    } else {
+        EXIT_NEGATIVE
+    }
+}
+
+fn export(path: &Path, name: Option<&str>, output: Option<&Path>) -> u8 {
+    let registry = Registry::builtin();
+    let Some(name) = name else {
+        for exporter in registry.iter() {
+            println!("{:<12}{}", exporter.name(), exporter.description());
+        }
+        return EXIT_OK;
+    };
+    let Some(exporter) = registry.get(name) else {
+        eprintln!(
+            "skyset: unknown exporter {name:?} (expected one of: {})",
+            registry.names().join(", ")
+        );
+        return EXIT_USAGE;
+    };
+    let skyset = match io::load_skyset(path) {
+        Ok(skyset) => skyset,
+        Err(err) => return report(err),
+    };
+    let result = exporter.render(&skyset).and_then(|contents| match output {
+        Some(output) => io::write_atomic(output, &contents),
+        None => {
+            print!("{contents}");
+            Ok(())
+        }
+    });
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(err) => report(err),
+    }
+}
+
+/// Prints a line for the current file and one per settled change until
+/// stdout goes away (e.g. the reading end of a pipe exits).
+fn watch(path: &Path, format: &WatchFormat) -> u8 {
+    let mut stdout = std::io::stdout().lock();
+    let mut emit = |skyset: &Skyset| match format.render(path, skyset) {
+        Ok(line) => writeln!(stdout, "{line}").and_then(|()| stdout.flush()),
+        Err(err) => {
+            eprintln!("skyset: {err}");
+            Ok(())
+        }
+    };
+
+    let mut updates = follow(path);
+    let mut result = Ok(());
+    while result.is_ok() {
+        match updates.next() {
+            Some(Ok(skyset)) => result = emit(&skyset),
+            Some(Err(err)) => eprintln!("skyset: {err}"),
+            None => break,
+        }
+    }
+    match result {
+        Err(err) if err.kind() != ErrorKind::BrokenPipe => {
+            eprintln!("skyset: {err}");
+            EXIT_IO
+        }
+        _ => EXIT_OK,
+    }
+}
+
+/// The current theme, then every change. Uses a running daemon when there
+/// is one so that many watchers do not each poll the file.
+#[cfg(unix)]
+fn follow(path: &Path) -> Box<dyn Iterator<Item = Result<Skyset, SkysetError>>> {
+    skyset::daemon::follow(path)
+}
+
+#[cfg(not(unix))]
+fn follow(path: &Path) -> Box<dyn Iterator<Item = Result<Skyset, SkysetError>>> {
+    Box::new(Watcher::new(path).into_updates())
+}
+
+#[cfg(unix)]
+fn daemon(path: &Path) -> u8 {
+    match skyset::daemon::Daemon::bind(path) {
+        Ok(daemon) => {
+            eprintln!("skyset: listening on {}", daemon.socket_path().display());
+            match daemon.run() {
+                Ok(()) => EXIT_OK,
+                Err(err) => report(err),
+            }
+        }
+        Err(err) => report(err),
+    }
+}
+
+#[cfg(not(unix))]
+fn daemon(_path: &Path) -> u8 {
+    eprintln!("skyset: the daemon needs Unix domain sockets");
+    EXIT_USAGE
+}
+
+fn history_command(path: &Path, action: HistoryAction) -> u8 {
+    let result = match action {
+        HistoryAction::List => history::list(path).map(|entries| {
+            if entries.is_empty() {
+                eprintln!(
+                    "skyset: no history in {}",
+                    history::history_dir(path).display()
+                );
+            }
+            for (idx, entry) in entries.iter().enumerate() {
+                println!("{:>3}  {}", idx + 1, history_line(entry));
+            }
+        }),
+        HistoryAction::Show(id) => history::find(path, &id).and_then(|entry| {
+            std::fs::read_to_string(&entry.path)
+                .map(|text| print!("{text}"))
+                .map_err(|err| SkysetError::from_io(entry.path.clone(), err))
+        }),
+        HistoryAction::Restore(id) => history::find(path, &id).and_then(|entry| {
+            history::restore(path, &entry).map(|_| eprintln!("restored {}", entry.id))
+        }),
+    };
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(err @ SkysetError::NotFound(_)) => {
+            eprintln!("skyset: {err}");
+            EXIT_NEGATIVE
+        }
+        Err(err) => report(err),
+    }
+}
+
+fn history_line(entry: &history::Entry) -> String {
+    match entry.load() {
+        Some(skyset) => format!(
+            "{}  {} {}  {}",
+            entry.id,
+            swatches(&skyset),
+            skyset.origin,
+            skyset.message
+        ),
+        None => format!("{}  (does not parse)", entry.id),
+    }
+}
+
+fn serve(path: &Path, host: &str, port: u16, cors: Option<String>) -> u8 {
+    match Server::bind(path, (host, port)) {
+        Ok(mut server) => {
+            if let Some(origin) = cors {
+                server = server.with_cors(origin);
+            }
+            if let Some(addr) = server.local_addr() {
+                eprintln!("skyset: serving http://{addr}/theme.json");
+            }
+            match server.run() {
+                Ok(()) => EXIT_OK,
+                Err(err) => report(err),
+            }
+        }
+        Err(err) => report(err),
+    }
+}
+
+fn apply_terminal(path: &Path) -> u8 {
+    let config = match TerminalConfig::load(&terminal_config_path(path)) {
+        Ok(config) => config,
+        Err(err) => return report(err),
+    };
+    let sequences = match io::load_skyset(path).and_then(|skyset| terminal::sequences(&skyset)) {
+        Ok(sequences) => sequences,
+        Err(err) => return report(err),
+    };
+    if let Err(err) = terminal::save_sequences(path, &sequences) {
+        return report(err);
+    }
+
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let _ = stdout
+            .write_all(sequences.as_bytes())
+            .and_then(|()| stdout.flush());
+    }
+    // A tty that has gone away or belongs to someone else should not keep
+    // the others from updating.
+    let mut failed = false;
+    for tty in config.tty_paths() {
+        if let Err(err) = terminal::write_to_tty(&tty, &sequences) {
+            eprintln!("skyset: {err}");
+            failed = true;
+        }
+    }
+    if failed { EXIT_NEGATIVE } else { EXIT_OK }
+}
+
+fn run_hooks(path: &Path, watch: bool) -> u8 {
+    let config = match HooksConfig::load(&hooks_path(path)) {
+        Ok(config) => config,
+        Err(err) => return report(err),
+    };
+    if config.hooks.is_empty() {
+        eprintln!("skyset: no hooks in {}", hooks_path(path).display());
+        return EXIT_NEGATIVE;
+    }
+
+    let run = |skyset: &Skyset| {
+        let outcomes = config.run(path, skyset);
+        for outcome in &outcomes {
+            match outcome.failure() {
+                Some(reason) => eprintln!("{}: {reason}", outcome.name),
+                None => println!("{}: ok", outcome.name),
+            }
+        }
+        outcomes.iter().all(|outcome| outcome.failure().is_none())
+    };
+
+    if !watch {
+        return match io::load_skyset(path) {
+            Ok(skyset) if run(&skyset) => EXIT_OK,
+            Ok(_) => EXIT_NEGATIVE,
+            Err(err) => report(err),
+        };
+    }
+    let mut watcher = Watcher::new(path);
+    loop {
+        match watcher.next_change() {
+            Ok(change) => {
+                run(&change.new);
+            }
+            Err(err) => eprintln!("skyset: {err}"),
+        }
+    }
+}
+
+fn diff_files(left: &Path, right: &Path, all: bool) -> u8 {
+    let (old, new) = match (io::load_skyset(left), io::load_skyset(right)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(err), _) | (_, Err(err)) => return report(err),
+    };
+
+    let changes: Vec<_> = diff::diff(&old, &new)
+        .into_iter()
+        .filter(|change| all || !change.is_bookkeeping())
+        .collect();
+    for change in &changes {
+        println!("{}", describe(change));
+    }
+    if changes.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_NEGATIVE
+    }
+}
+
+fn describe(change: &FieldChange) -> String {
+    let side = |value: &Option<Value>| {
+        value
+            .as_ref()
+            .map_or("(absent)".to_string(), Value::to_string)
+    };
+    format!(
+        "{}: {} -> {}",
+        change.path,
+        side(&change.before),
+        side(&change.after)
+    )
+}
+
+fn report(err: SkysetError) -> u8 {
+    eprintln!("skyset: {err}");
+    EXIT_IO
+}
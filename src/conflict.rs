@@ -0,0 +1,125 @@
+use skyset::{
+    field::{FIELD_ORDER, FieldId, field_value, set_field_value},
+    io::DiskStamp,
+    model::Skyset,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    Mine,
+    Theirs,
+}
+
+/// Unsaved in-memory edits versus a newer version written by someone else.
+pub struct Conflict {
+    theirs: Skyset,
+    theirs_raw: String,
+    theirs_stamp: Option<DiskStamp>,
+    fields: Vec<(FieldId, Choice)>,
+    selected: usize,
+    merging: bool,
+}
+
+impl Conflict {
+    pub fn new(
+        mine: &Skyset,
+        theirs: Skyset,
+        theirs_raw: String,
+        theirs_stamp: Option<DiskStamp>,
+    ) -> Self {
+        let fields = FIELD_ORDER
+            .iter()
+            .filter(|field| field_value(mine, **field) != field_value(&theirs, **field))
+            .map(|field| (*field, Choice::Mine))
+            .collect();
+        Self {
+            theirs,
+            theirs_raw,
+            theirs_stamp,
+            fields,
+            selected: 0,
+            merging: false,
+        }
+    }
+
+    pub fn theirs(&self) -> &Skyset {
+        &self.theirs
+    }
+
+    pub fn theirs_raw(&self) -> &str {
+        &self.theirs_raw
+    }
+
+    /// The disk version, which becomes the new baseline once resolved.
+    pub fn into_theirs(self) -> (Skyset, String, Option<DiskStamp>) {
+        (self.theirs, self.theirs_raw, self.theirs_stamp)
+    }
+
+    pub fn fields(&self) -> &[(FieldId, Choice)] {
+        &self.fields
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn is_merging(&self) -> bool {
+        self.merging
+    }
+
+    pub fn set_merging(&mut self, merging: bool) {
+        self.merging = merging;
+    }
+
+    pub fn next(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + 1) % self.fields.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some((_, choice)) = self.fields.get_mut(self.selected) {
+            *choice = match choice {
+                Choice::Mine => Choice::Theirs,
+                Choice::Theirs => Choice::Mine,
+            };
+        }
+    }
+
+    /// Our edits with the fields marked `Theirs` taken from the disk version.
+    pub fn merged(&self, mine: &Skyset) -> Skyset {
+        let mut merged = mine.clone();
+        for (field, choice) in &self.fields {
+            if *choice == Choice::Theirs {
+                let _ = set_field_value(&mut merged, *field, field_value(&self.theirs, *field));
+            }
+        }
+        merged
+    }
+}
+
+/// Whether any editor field differs, i.e. a conflict would list anything.
+pub fn fields_differ(a: &Skyset, b: &Skyset) -> bool {
+    FIELD_ORDER
+        .iter()
+        .any(|field| field_value(a, *field) != field_value(b, *field))
+}
+
+/// `mine` with everything the editor does not show (`_version`,
+/// `updated_at` and unknown keys) taken from `theirs`.
+pub fn rebase(mine: &Skyset, theirs: &Skyset) -> Skyset {
+    let mut rebased = mine.clone();
+    rebased.version = theirs.version;
+    rebased.updated_at = theirs.updated_at.clone();
+    rebased.extra = theirs.extra.clone();
+    rebased.theme.extra = theirs.theme.extra.clone();
+    rebased.palette.extra = theirs.palette.extra.clone();
+    rebased.gradients.extra = theirs.gradients.extra.clone();
+    rebased
+}
@@ -0,0 +1,288 @@
+//! Publishing changes over a Unix domain socket.
+//!
+//! `skyset daemon` watches `latest.yml` and listens on `skyset.sock` next to
+//! it. Every client that connects is subscribed: it gets a `snapshot`
+//! message right away and an `update` message for every change after that,
+//! one JSON object per line:
+//!
+//! ```text
+//! {"type":"snapshot","skyset":{...}}
+//! {"type":"update","skyset":{...},"changed":["theme.accent"]}
+//! ```
+//!
+//! The file stays the source of truth and the daemon is optional: [`follow`]
+//! uses the socket when a daemon is running and watches the file otherwise.
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::SkysetError, model::Skyset, watch::Watcher};
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Message {
+    Snapshot {
+        skyset: Skyset,
+    },
+    Update {
+        skyset: Skyset,
+        /// Paths of the values that changed, as in `diff::FieldChange`.
+        changed: Vec<String>,
+    },
+    /// The file could not be read; subscribers keep the last good values.
+    Error {
+        message: String,
+    },
+}
+
+impl Message {
+    /// The theme carried by a snapshot or update.
+    pub fn skyset(&self) -> Option<&Skyset> {
+        match self {
+            Message::Snapshot { skyset } | Message::Update { skyset, .. } => Some(skyset),
+            Message::Error { .. } => None,
+        }
+    }
+}
+
+/// `skyset.sock` in the same directory as `config_path`.
+pub fn socket_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("skyset.sock")
+}
+
+struct Subscribers {
+    current: Skyset,
+    clients: Vec<UnixStream>,
+}
+
+pub struct Daemon {
+    config_path: PathBuf,
+    socket_path: PathBuf,
+    listener: UnixListener,
+}
+
+impl Daemon {
+    /// Binds the socket next to `config_path`, replacing a stale socket file
+    /// left by a daemon that is no longer running.
+    pub fn bind(config_path: &Path) -> Result<Self, SkysetError> {
+        let socket_path = socket_path(config_path);
+        let io_error = |err| SkysetError::from_io(socket_path.clone(), err);
+        if socket_path.exists() {
+            if UnixStream::connect(&socket_path).is_ok() {
+                return Err(io_error(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "a skyset daemon is already running",
+                )));
+            }
+            fs::remove_file(&socket_path).map_err(io_error)?;
+        }
+        if let Some(parent) = socket_path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let listener = UnixListener::bind(&socket_path).map_err(io_error)?;
+        Ok(Self {
+            config_path: config_path.to_path_buf(),
+            socket_path,
+            listener,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Serves subscribers until the process exits.
+    pub fn run(self) -> Result<(), SkysetError> {
+        let mut watcher = Watcher::new(&self.config_path);
+        let subscribers = Arc::new(Mutex::new(Subscribers {
+            current: watcher.current().clone(),
+            clients: Vec::new(),
+        }));
+
+        let listener = self
+            .listener
+            .try_clone()
+            .map_err(|err| SkysetError::from_io(self.socket_path.clone(), err))?;
+        let accepting = Arc::clone(&subscribers);
+        thread::spawn(move || {
+            for mut client in listener.incoming().flatten() {
+                // A subscriber that stops reading is dropped rather than
+                // stalling everyone else.
+                let _ = client.set_write_timeout(Some(WRITE_TIMEOUT));
+                // The snapshot is sent without holding the lock, so updates
+                // are not held up by a slow new client; if one went out in
+                // the meantime, the client gets a fresh snapshot instead.
+                loop {
+                    let Ok(current) = accepting
+                        .lock()
+                        .map(|subscribers| subscribers.current.clone())
+                    else {
+                        return;
+                    };
+                    let snapshot = Message::Snapshot {
+                        skyset: current.clone(),
+                    };
+                    if send(&mut client, &snapshot).is_err() {
+                        break;
+                    }
+                    let Ok(mut subscribers) = accepting.lock() else {
+                        return;
+                    };
+                    if subscribers.current == current {
+                        subscribers.clients.push(client);
+                        break;
+                    }
+                }
+            }
+        });
+
+        loop {
+            let message = match watcher.next_change() {
+                Ok(change) => Message::Update {
+                    changed: change
+                        .changed
+                        .into_iter()
+                        .map(|change| change.path)
+                        .collect(),
+                    skyset: change.new,
+                },
+                Err(err) => Message::Error {
+                    message: err.to_string(),
+                },
+            };
+            let Ok(mut subscribers) = subscribers.lock() else {
+                return Ok(());
+            };
+            if let Some(skyset) = message.skyset() {
+                subscribers.current = skyset.clone();
+            }
+            subscribers
+                .clients
+                .retain_mut(|client| send(client, &message).is_ok());
+        }
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.socket_path);
+    }
+}
+
+fn send(client: &mut UnixStream, message: &Message) -> io::Result<()> {
+    let mut line = serde_json::to_string(message).map_err(io::Error::other)?;
+    line.push('\n');
+    client.write_all(line.as_bytes())
+}
+
+/// A connection to a running daemon, yielding its messages in order.
+pub struct Subscription {
+    socket_path: PathBuf,
+    lines: io::Lines<BufReader<UnixStream>>,
+}
+
+/// Connects to the daemon for `config_path`. Fails if none is running.
+pub fn subscribe(config_path: &Path) -> Result<Subscription, SkysetError> {
+    let socket_path = socket_path(config_path);
+    let stream = UnixStream::connect(&socket_path)
+        .map_err(|err| SkysetError::from_io(socket_path.clone(), err))?;
+    Ok(Subscription {
+        socket_path,
+        lines: BufReader::new(stream).lines(),
+    })
+}
+
+impl Iterator for Subscription {
+    type Item = Result<Message, SkysetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(err) => return Some(Err(SkysetError::from_io(self.socket_path.clone(), err))),
+        };
+        Some(
+            serde_json::from_str(&line).map_err(|err| SkysetError::Parse {
+                path: self.socket_path.clone(),
+                message: err.to_string(),
+                line: None,
+                column: None,
+            }),
+        )
+    }
+}
+
+/// The current theme and then every change, from the daemon if one is
+/// running, otherwise (or once the daemon goes away) by watching the file.
+pub fn follow(config_path: &Path) -> Box<dyn Iterator<Item = Result<Skyset, SkysetError>>> {
+    let Ok(subscription) = subscribe(config_path) else {
+        return Box::new(Watcher::new(config_path).into_updates());
+    };
+    let path = config_path.to_path_buf();
+    let fallback = std::iter::once(()).flat_map(move |()| Watcher::new(&path).into_updates());
+    let mut last: Option<Skyset> = None;
+    Box::new(
+        subscription
+            .map_while(|message| message.ok())
+            .filter_map(|message| match message {
+                Message::Snapshot { skyset } | Message::Update { skyset, .. } => Some(Ok(skyset)),
+                Message::Error { .. } => None,
+            })
+            .chain(fallback)
+            // The fallback starts with the current values, which the daemon
+            // has usually sent already.
+            .filter(move |update| match update {
+                Ok(skyset) if last.as_ref() == Some(skyset) => false,
+                Ok(skyset) => {
+                    last = Some(skyset.clone());
+                    true
+                }
+                Err(_) => true,
+            }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::save_skyset, test_support::TempDir};
+
+    #[cfg(unix)]
+    #[test]
+    fn daemon_sends_snapshot_then_updates() {
+        let dir = TempDir::new("daemon");
+        let path = dir.join("latest.yml");
+        let mut skyset = Skyset::default();
+        save_skyset(&path, &mut skyset).unwrap();
+
+        let server = Daemon::bind(&path).unwrap();
+        assert!(Daemon::bind(&path).is_err());
+        std::thread::spawn(move || server.run());
+
+        let mut subscription = subscribe(&path).unwrap();
+        let first = subscription.next().unwrap().unwrap();
+        assert!(matches!(first, Message::Snapshot { .. }));
+
+        skyset.message = "from the producer".to_string();
+        save_skyset(&path, &mut skyset).unwrap();
+        let update = subscription.next().unwrap().unwrap();
+
+        match update {
+            Message::Update { skyset, changed } => {
+                assert_eq!(skyset.message, "from the producer");
+                assert!(changed.contains(&"message".to_string()));
+            }
+            other => panic!("expected an update, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use serde_json::Value;
+
+use crate::{field::FieldId, model::Skyset};
+
+/// One leaf value that differs between two `Skyset`s.
+///
+/// `path` uses dotted keys and `[index]` for gradient stops, e.g.
+/// `theme.accent` or `gradients.background[1]`. `None` means the value is
+/// absent on that side (a gradient grew or shrank, an extension key came or
+/// went).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl FieldChange {
+    /// The editor field this change maps to, if any.
+    pub fn field(&self) -> Option<FieldId> {
+        FieldId::from_path(&self.path)
+    }
+
+    /// `updated_at` and `_version`, which every save stamps whether or not
+    /// a value changed.
+    pub fn is_bookkeeping(&self) -> bool {
+        matches!(self.path.as_str(), "updated_at" | "_version")
+    }
+}
+
+pub fn diff(old: &Skyset, new: &Skyset) -> Vec<FieldChange> {
+    let before = flatten(old);
+    let after = flatten(new);
+
+    let mut changes = Vec::new();
+    for (path, value) in &after {
+        let previous = before.iter().find(|(key, _)| key == path).map(|(_, v)| v);
+        if previous != Some(value) {
+            changes.push(FieldChange {
+                path: path.clone(),
+                before: previous.cloned(),
+                after: Some(value.clone()),
+            });
+        }
+    }
+    for (path, value) in &before {
+        if !after.iter().any(|(key, _)| key == path) {
+            changes.push(FieldChange {
+                path: path.clone(),
+                before: Some(value.clone()),
+                after: None,
+            });
+        }
+    }
+    changes
+}
+
+/// Every leaf value of `skyset` with its path, in document order.
+pub fn flatten(skyset: &Skyset) -> Vec<(String, Value)> {
+    let mut leaves = Vec::new();
+    if let Ok(value) = serde_json::to_value(skyset) {
+        flatten_into(&value, String::new(), &mut leaves);
+    }
+    leaves
+}
+
+fn flatten_into(value: &Value, path: String, leaves: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                flatten_into(child, child_path, leaves);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (idx, child) in items.iter().enumerate() {
+                flatten_into(child, format!("{path}[{idx}]"), leaves);
+            }
+        }
+        _ => leaves.push((path, value.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_flags_bookkeeping_fields() {
+        let before = Skyset::default();
+        let mut after = before.clone();
+        after.version += 1;
+        after.updated_at = "2026-03-02T08:30:00Z".to_string();
+        after.theme.accent = "#222222".to_string();
+
+        let bookkeeping: Vec<_> = diff(&before, &after)
+            .iter()
+            .map(|c| (c.path.clone(), c.is_bookkeeping()))
+            .collect();
+        assert_eq!(
+            bookkeeping,
+            [
+                ("_version".to_string(), true),
+                ("updated_at".to_string(), true),
+                ("theme.accent".to_string(), false),
+            ]
+        );
+    }
+}
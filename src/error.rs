@@ -0,0 +1,80 @@
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum SkysetError {
+    NotFound(PathBuf),
+    PermissionDenied(PathBuf),
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    Serialize(String),
+}
+
+impl SkysetError {
+    pub fn from_io(path: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::NotFound => SkysetError::NotFound(path),
+            io::ErrorKind::PermissionDenied => SkysetError::PermissionDenied(path),
+            _ => SkysetError::Io { path, source },
+        }
+    }
+
+    pub fn from_yaml(path: PathBuf, err: serde_yaml::Error) -> Self {
+        let location = err.location();
+        let mut message = err.to_string();
+        if let Some(loc) = &location {
+            let suffix = format!(" at line {} column {}", loc.line(), loc.column());
+            if let Some(stripped) = message.strip_suffix(&suffix) {
+                message = stripped.to_string();
+            }
+        }
+        SkysetError::Parse {
+            path,
+            message,
+            line: location.as_ref().map(|loc| loc.line()),
+            column: location.as_ref().map(|loc| loc.column()),
+        }
+    }
+}
+
+impl fmt::Display for SkysetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkysetError::NotFound(path) => write!(f, "{} not found", path.display()),
+            SkysetError::PermissionDenied(path) => {
+                write!(f, "permission denied: {}", path.display())
+            }
+            SkysetError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            SkysetError::Parse {
+                path,
+                message,
+                line: Some(line),
+                column: Some(column),
+            } => write!(
+                f,
+                "parse error in {} at line {line}, column {column}: {message}",
+                path.display()
+            ),
+            SkysetError::Parse { path, message, .. } => {
+                write!(f, "parse error in {}: {message}", path.display())
+            }
+            SkysetError::Serialize(message) => write!(f, "could not serialize skyset: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SkysetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SkysetError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Rendering a `Skyset` into other programs' config formats.
+//!
+//! Each target is an [`Exporter`]; `skyset export <name>` looks it up in a
+//! [`Registry`]. Exporters for config files rarely want the raw schema:
+//! they go through [`Roles`], which maps the theme onto the color roles
+//! most formats share (accent, surfaces, gradient stops) and replaces
+//! anything that does not parse with the default theme's value, so one
+//! bad field cannot produce a broken config.
+
+mod alacritty;
+mod kitty;
+mod sequences;
+mod web;
+mod wezterm;
+
+pub use alacritty::Alacritty;
+pub use kitty::{Kitty, KittySetColors};
+pub use sequences::Sequences;
+pub use web::{Css, Scss, Tailwind, TailwindJson};
+pub use wezterm::WezTerm;
+
+use crate::{
+    color::{
+        contrast_ratio, format_hex, hsl_to_rgb, mix, parse_hex_rgb, relative_luminance, rgb_to_hsl,
+    },
+    error::SkysetError,
+    model::{Skyset, ThemeMode},
+};
+
+/// One output format.
+pub trait Exporter {
+    /// What `skyset export` is given, e.g. `json`.
+    fn name(&self) -> &str;
+
+    /// Other names accepted for this exporter.
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// One line for `skyset export` without a name.
+    fn description(&self) -> &str;
+
+    /// Usual file extension of the output, without the dot.
+    fn extension(&self) -> &str;
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError>;
+}
+
+/// The exporters `skyset export` knows about, in listing order.
+pub struct Registry {
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl Registry {
+    /// An empty registry, for callers that only want their own exporters.
+    pub fn empty() -> Self {
+        Self {
+            exporters: Vec::new(),
+        }
+    }
+
+    /// Every exporter that ships with skyset.
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(Json));
+        registry.register(Box::new(Yaml));
+        registry.register(Box::new(Kitty));
+        registry.register(Box::new(KittySetColors));
+        registry.register(Box::new(Alacritty));
+        registry.register(Box::new(WezTerm));
+        registry.register(Box::new(Sequences));
+        registry.register(Box::new(Css));
+        registry.register(Box::new(Scss));
+        registry.register(Box::new(Tailwind));
+        registry.register(Box::new(TailwindJson));
+        registry
+    }
+
+    /// Adds `exporter`, replacing any exporter with the same name.
+    pub fn register(&mut self, exporter: Box<dyn Exporter>) {
+        match self
+            .exporters
+            .iter()
+            .position(|existing| existing.name() == exporter.name())
+        {
+            Some(idx) => self.exporters[idx] = exporter,
+            None => self.exporters.push(exporter),
+        }
+    }
+
+    /// Looks an exporter up by name or alias.
+    pub fn get(&self, name: &str) -> Option<&dyn Exporter> {
+        self.exporters
+            .iter()
+            .find(|exporter| exporter.name() == name || exporter.aliases().contains(&name))
+            .map(|exporter| exporter.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Exporter> {
+        self.exporters.iter().map(|exporter| exporter.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.iter().map(|exporter| exporter.name()).collect()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Text needs at least this contrast ratio against the background (WCAG AA).
+const MIN_CONTRAST: f64 = 4.5;
+
+/// Red, green, yellow, blue, magenta and cyan, in ANSI order.
+const ANSI_HUES: [f64; 6] = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
+
+/// How far (in degrees) an ANSI hue may lean toward the accent.
+const MAX_HUE_SHIFT: f64 = 15.0;
+
+/// The theme's colors by role, each as `#RRGGBB`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Roles {
+    pub mode: ThemeMode,
+    /// `theme.mode`, or for `system` and unknown modes, whether `surface`
+    /// is dark.
+    pub dark: bool,
+    /// `theme.accent`: cursors, selections, highlights.
+    pub accent: String,
+    /// `palette.primary`: the main background.
+    pub surface: String,
+    /// `palette.secondary`: panels and inactive areas on top of `surface`.
+    pub surface_raised: String,
+    /// `palette.tertiary`: borders, overlays and the most raised elements.
+    pub surface_overlay: String,
+    /// `gradients.background`, first stop first. Never empty.
+    pub background: Vec<String>,
+    /// `gradients.hero`, first stop first. Never empty.
+    pub hero: Vec<String>,
+    /// Text on `surface`: light for dark themes and dark for light ones,
+    /// tinted with the accent hue, with at least 4.5:1 contrast.
+    pub foreground: String,
+    /// Terminal colors 0-15, derived from the surfaces, `foreground` and the
+    /// accent's hue and saturation.
+    pub ansi: [String; 16],
+}
+
+impl Roles {
+    pub fn new(skyset: &Skyset) -> Self {
+        let defaults = Skyset::default();
+        let surface = color_or(&skyset.palette.primary, &defaults.palette.primary);
+        let (r, g, b) = rgb(&surface);
+        let dark = match skyset.theme.mode {
+            ThemeMode::Dark => true,
+            ThemeMode::Light => false,
+            ThemeMode::System | ThemeMode::Unknown => relative_luminance(r, g, b) < 0.5,
+        };
+        Self::derive(Self {
+            mode: skyset.theme.mode,
+            dark,
+            accent: color_or(&skyset.theme.accent, &defaults.theme.accent),
+            surface,
+            surface_raised: color_or(&skyset.palette.secondary, &defaults.palette.secondary),
+            surface_overlay: color_or(&skyset.palette.tertiary, &defaults.palette.tertiary),
+            background: stops_or(&skyset.gradients.background, &defaults.gradients.background),
+            hero: stops_or(&skyset.gradients.hero, &defaults.gradients.hero),
+            foreground: String::new(),
+            ansi: Default::default(),
+        })
+    }
+
+    /// The same theme for the other color scheme: the surfaces and
+    /// background stops with their lightness mirrored (so a near-black
+    /// becomes a near-white of the same hue), and a foreground and ANSI
+    /// colors to match. The accent and hero stops are kept.
+    pub fn flipped(&self) -> Self {
+        let mirror = |value: &String| {
+            let (r, g, b) = rgb(value);
+            let (hue, saturation, lightness) = rgb_to_hsl(r, g, b);
+            hex(hsl_to_rgb(hue, saturation, 1.0 - lightness))
+        };
+        Self::derive(Self {
+            mode: self.mode,
+            dark: !self.dark,
+            accent: self.accent.clone(),
+            surface: mirror(&self.surface),
+            surface_raised: mirror(&self.surface_raised),
+            surface_overlay: mirror(&self.surface_overlay),
+            background: self.background.iter().map(mirror).collect(),
+            hero: self.hero.clone(),
+            foreground: String::new(),
+            ansi: Default::default(),
+        })
+    }
+
+    /// Fills in `foreground` and `ansi` from the other roles.
+    fn derive(mut roles: Self) -> Self {
+        let foreground = readable_foreground(rgb(&roles.accent), rgb(&roles.surface), roles.dark);
+        let ansi = ansi_colors(
+            rgb(&roles.accent),
+            [
+                rgb(&roles.surface),
+                rgb(&roles.surface_raised),
+                rgb(&roles.surface_overlay),
+            ],
+            foreground,
+            roles.dark,
+        );
+        roles.foreground = hex(foreground);
+        roles.ansi = ansi.map(hex);
+        roles
+    }
+}
+
+/// A color already normalized by [`color_or`] or [`stops_or`].
+fn rgb(value: &str) -> (u8, u8, u8) {
+    parse_hex_rgb(value).unwrap_or_default()
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format_hex(r, g, b)
+}
+
+/// A near-white or near-black in the accent's hue, whichever `dark` asks
+/// for, unless the surface does not match the mode and the other one (or
+/// plain white or black) reads better.
+fn readable_foreground(accent: (u8, u8, u8), surface: (u8, u8, u8), dark: bool) -> (u8, u8, u8) {
+    let (hue, _, _) = rgb_to_hsl(accent.0, accent.1, accent.2);
+    let light_text = hsl_to_rgb(hue, 0.15, 0.90);
+    let dark_text = hsl_to_rgb(hue, 0.20, 0.12);
+    let (preferred, other) = if dark {
+        (light_text, dark_text)
+    } else {
+        (dark_text, light_text)
+    };
+    [preferred, other, (255, 255, 255), (0, 0, 0)]
+        .into_iter()
+        .find(|candidate| contrast_ratio(*candidate, surface) >= MIN_CONTRAST)
+        .unwrap_or(preferred)
+}
+
+/// Black, the six hues, white, then their bright variants. The hues keep
+/// their usual meaning (red is still red) but lean up to
+/// [`MAX_HUE_SHIFT`] degrees toward the accent and share its saturation.
+fn ansi_colors(
+    accent: (u8, u8, u8),
+    [surface, raised, overlay]: [(u8, u8, u8); 3],
+    foreground: (u8, u8, u8),
+    dark: bool,
+) -> [(u8, u8, u8); 16] {
+    let (accent_hue, accent_saturation, _) = rgb_to_hsl(accent.0, accent.1, accent.2);
+    let saturation = accent_saturation.clamp(0.45, 0.80);
+    let (normal, bright) = if dark { (0.62, 0.74) } else { (0.38, 0.30) };
+    let hued = |base: f64, lightness: f64| {
+        // A grey accent has no meaningful hue to lean toward.
+        let delta = if accent_saturation < 0.1 {
+            0.0
+        } else {
+            (accent_hue - base + 540.0).rem_euclid(360.0) - 180.0
+        };
+        let hue = base + (delta * 0.25).clamp(-MAX_HUE_SHIFT, MAX_HUE_SHIFT);
+        hsl_to_rgb(hue, saturation, lightness)
+    };
+    let (black, bright_black, white, bright_white) = if dark {
+        (raised, overlay, mix(foreground, surface, 0.25), foreground)
+    } else {
+        (foreground, mix(foreground, surface, 0.4), overlay, raised)
+    };
+
+    let mut colors = [(0, 0, 0); 16];
+    colors[0] = black;
+    colors[7] = white;
+    colors[8] = bright_black;
+    colors[15] = bright_white;
+    for (idx, base) in ANSI_HUES.into_iter().enumerate() {
+        colors[idx + 1] = hued(base, normal);
+        colors[idx + 9] = hued(base, bright);
+    }
+    colors
+}
+
+/// `value` as `#RRGGBB`, or `fallback` if it is not a color.
+fn color_or(value: &str, fallback: &str) -> String {
+    parse_hex_rgb(value)
+        .or_else(|| parse_hex_rgb(fallback))
+        .map(|(r, g, b)| format_hex(r, g, b))
+        .unwrap_or_default()
+}
+
+/// The stops that parse, or all of `fallback` if none do.
+fn stops_or(stops: &[String], fallback: &[String]) -> Vec<String> {
+    let valid: Vec<String> = stops
+        .iter()
+        .filter_map(|stop| parse_hex_rgb(stop))
+        .map(|(r, g, b)| format_hex(r, g, b))
+        .collect();
+    if valid.is_empty() {
+        fallback.to_vec()
+    } else {
+        valid
+    }
+}
+
+/// The whole `Skyset` as pretty-printed JSON.
+pub struct Json;
+
+impl Exporter for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn description(&self) -> &str {
+        "the whole file as JSON"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        serde_json::to_string_pretty(skyset)
+            .map(|json| json + "\n")
+            .map_err(|err| SkysetError::Serialize(err.to_string()))
+    }
+}
+
+/// The whole `Skyset` as YAML, without the comments of the original file.
+pub struct Yaml;
+
+impl Exporter for Yaml {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["yml"]
+    }
+
+    fn description(&self) -> &str {
+        "the whole file as YAML"
+    }
+
+    fn extension(&self) -> &str {
+        "yml"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        serde_yaml::to_string(skyset).map_err(|err| SkysetError::Serialize(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_roles_normalize_and_fall_back() {
+        let mut skyset = Skyset::default();
+        skyset.theme.accent = "#4fc3f7".to_string();
+        skyset.palette.secondary = "teal".to_string();
+        skyset.gradients.hero = vec!["nope".to_string()];
+
+        let roles = Roles::new(&skyset);
+        let defaults = Skyset::default();
+        assert_eq!(roles.accent, "#4FC3F7");
+        assert_eq!(roles.surface_raised, defaults.palette.secondary);
+        assert_eq!(roles.hero, defaults.gradients.hero);
+    }
+
+    #[test]
+    fn export_foreground_follows_mode_and_stays_readable() {
+        let contrast = |roles: &Roles| {
+            let rgb = |hex: &str| parse_hex_rgb(hex).unwrap();
+            contrast_ratio(rgb(&roles.foreground), rgb(&roles.surface))
+        };
+        let luminance = |hex: &str| {
+            let (r, g, b) = parse_hex_rgb(hex).unwrap();
+            relative_luminance(r, g, b)
+        };
+
+        let mut skyset = Skyset::default();
+        let dark = Roles::new(&skyset);
+        assert!(luminance(&dark.foreground) > 0.5);
+        assert!(contrast(&dark) >= 4.5);
+
+        skyset.theme.mode = ThemeMode::Light;
+        skyset.palette.primary = "#FAFAF7".to_string();
+        let light = Roles::new(&skyset);
+        assert!(luminance(&light.foreground) < 0.5);
+        assert!(contrast(&light) >= 4.5);
+
+        // A light surface in dark mode still gets dark text.
+        skyset.theme.mode = ThemeMode::Dark;
+        assert!(contrast(&Roles::new(&skyset)) >= 4.5);
+    }
+}
@@ -0,0 +1,61 @@
+//! Alacritty: a TOML file to `import` from `alacritty.toml`.
+
+use super::{Exporter, Roles};
+use crate::{error::SkysetError, model::Skyset};
+
+const NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+pub struct Alacritty;
+
+impl Exporter for Alacritty {
+    fn name(&self) -> &str {
+        "alacritty"
+    }
+
+    fn description(&self) -> &str {
+        "Alacritty colors, for `general.import` in alacritty.toml"
+    }
+
+    fn extension(&self) -> &str {
+        "toml"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let roles = Roles::new(skyset);
+        let mut toml = String::from(
+            "# Generated by `skyset export alacritty`. Add to alacritty.toml:\n#   [general]\n#   import = [\"~/.config/alacritty/skyset.toml\"]\n",
+        );
+        let mut table = |name: &str, entries: &[(&str, &str)]| {
+            toml.push_str(&format!("\n[colors.{name}]\n"));
+            for (key, value) in entries {
+                toml.push_str(&format!("{key} = \"{value}\"\n"));
+            }
+        };
+        table(
+            "primary",
+            &[
+                ("background", &roles.surface),
+                ("foreground", &roles.foreground),
+            ],
+        );
+        table(
+            "cursor",
+            &[("text", &roles.surface), ("cursor", &roles.accent)],
+        );
+        table(
+            "selection",
+            &[("text", &roles.surface), ("background", &roles.accent)],
+        );
+        for (name, colors) in [("normal", &roles.ansi[..8]), ("bright", &roles.ansi[8..])] {
+            let entries: Vec<(&str, &str)> = NAMES
+                .iter()
+                .zip(colors)
+                .map(|(key, value)| (*key, value.as_str()))
+                .collect();
+            table(name, &entries);
+        }
+        Ok(toml)
+    }
+}
@@ -0,0 +1,74 @@
+//! kitty: a `.conf` include and the matching `kitty @ set-colors` command.
+
+use super::{Exporter, Roles};
+use crate::{error::SkysetError, model::Skyset};
+
+/// `kitty.conf` settings, in the order they are written.
+fn settings(roles: &Roles) -> Vec<(String, &str)> {
+    let mut settings = vec![
+        ("background".to_string(), roles.surface.as_str()),
+        ("foreground".to_string(), roles.foreground.as_str()),
+        ("selection_background".to_string(), roles.accent.as_str()),
+        ("selection_foreground".to_string(), roles.surface.as_str()),
+        ("cursor".to_string(), roles.accent.as_str()),
+        ("cursor_text_color".to_string(), roles.surface.as_str()),
+    ];
+    for (idx, color) in roles.ansi.iter().enumerate() {
+        settings.push((format!("color{idx}"), color.as_str()));
+    }
+    settings
+}
+
+/// A file to `include` from `kitty.conf`.
+pub struct Kitty;
+
+impl Exporter for Kitty {
+    fn name(&self) -> &str {
+        "kitty"
+    }
+
+    fn description(&self) -> &str {
+        "kitty colors, for `include skyset.conf` in kitty.conf"
+    }
+
+    fn extension(&self) -> &str {
+        "conf"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let mut conf = String::from(
+            "# Generated by `skyset export kitty`. Add to kitty.conf:\n#   include skyset.conf\n\n",
+        );
+        for (key, value) in settings(&Roles::new(skyset)) {
+            conf.push_str(&format!("{key:<22}{value}\n"));
+        }
+        Ok(conf)
+    }
+}
+
+/// The same colors as a `kitty @ set-colors` command line, to recolor
+/// running windows over remote control (`allow_remote_control yes`).
+pub struct KittySetColors;
+
+impl Exporter for KittySetColors {
+    fn name(&self) -> &str {
+        "kitty-set-colors"
+    }
+
+    fn description(&self) -> &str {
+        "a `kitty @ set-colors` command that applies the kitty colors live"
+    }
+
+    fn extension(&self) -> &str {
+        "sh"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let mut command = String::from("kitty @ set-colors --all --configured");
+        for (key, value) in settings(&Roles::new(skyset)) {
+            command.push_str(&format!(" {key}={value}"));
+        }
+        command.push('\n');
+        Ok(command)
+    }
+}
@@ -0,0 +1,38 @@
+//! OSC escape sequences that recolor a running terminal in place.
+
+use super::{Exporter, Roles};
+use crate::{error::SkysetError, model::Skyset};
+
+/// OSC 4 for colors 0-15, then OSC 10, 11 and 12 for the foreground,
+/// background and cursor, each terminated with ST.
+pub struct Sequences;
+
+impl Exporter for Sequences {
+    fn name(&self) -> &str {
+        "sequences"
+    }
+
+    fn description(&self) -> &str {
+        "OSC escape sequences; `cat` them to recolor the current terminal"
+    }
+
+    fn extension(&self) -> &str {
+        "txt"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let roles = Roles::new(skyset);
+        let mut sequences = String::new();
+        for (idx, color) in roles.ansi.iter().enumerate() {
+            sequences.push_str(&format!("\x1b]4;{idx};{color}\x1b\\"));
+        }
+        for (code, color) in [
+            (10, &roles.foreground),
+            (11, &roles.surface),
+            (12, &roles.accent),
+        ] {
+            sequences.push_str(&format!("\x1b]{code};{color}\x1b\\"));
+        }
+        Ok(sequences)
+    }
+}
@@ -0,0 +1,266 @@
+//! CSS custom properties, SCSS variables and Tailwind presets.
+//!
+//! All of them carry the same `--skyset-*` properties: every color role,
+//! each gradient stop, and the gradients themselves as `linear-gradient()`
+//! values. For `theme.mode: dark` or `light` they set `color-scheme` to
+//! match. For `system`, the file's palette is used for the scheme it suits
+//! and a `prefers-color-scheme` block switches to [`Roles::flipped`] for the
+//! other one.
+
+use serde_json::{Map, Value, json};
+
+use super::{Exporter, Roles};
+use crate::{
+    error::SkysetError,
+    model::{Skyset, ThemeMode, theme_mode_label},
+};
+
+/// `:root` rules: the unconditional ones, then an optional media query.
+struct Rules {
+    root: Vec<(String, String)>,
+    /// `(media condition, properties)` for the other scheme in system mode.
+    other_scheme: Option<(&'static str, Vec<(String, String)>)>,
+}
+
+fn rules(skyset: &Skyset) -> Rules {
+    let roles = Roles::new(skyset);
+    let scheme = |dark: bool| if dark { "dark" } else { "light" };
+    let system = roles.mode == ThemeMode::System;
+
+    let mut root = vec![
+        (
+            "color-scheme".to_string(),
+            if system {
+                "light dark"
+            } else {
+                scheme(roles.dark)
+            }
+            .to_string(),
+        ),
+        (
+            "--skyset-mode".to_string(),
+            theme_mode_label(roles.mode).to_string(),
+        ),
+    ];
+    root.extend(properties(&roles));
+    let other_scheme = system.then(|| {
+        let flipped = roles.flipped();
+        let condition = if flipped.dark {
+            "(prefers-color-scheme: dark)"
+        } else {
+            "(prefers-color-scheme: light)"
+        };
+        (condition, properties(&flipped))
+    });
+    Rules { root, other_scheme }
+}
+
+/// `--skyset-*` custom properties for every color role and gradient.
+fn properties(roles: &Roles) -> Vec<(String, String)> {
+    let mut properties = vec![
+        ("accent", roles.accent.clone()),
+        ("foreground", roles.foreground.clone()),
+        ("primary", roles.surface.clone()),
+        ("secondary", roles.surface_raised.clone()),
+        ("tertiary", roles.surface_overlay.clone()),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect::<Vec<_>>();
+    for (name, stops, angle) in [
+        ("background", &roles.background, 180),
+        ("hero", &roles.hero, 135),
+    ] {
+        for (idx, stop) in stops.iter().enumerate() {
+            properties.push((format!("{name}-{}", idx + 1), stop.clone()));
+        }
+        properties.push((name.to_string(), linear_gradient(angle, stops)));
+    }
+    properties
+        .into_iter()
+        .map(|(name, value)| (format!("--skyset-{name}"), value))
+        .collect()
+}
+
+/// A gradient needs two stops, so a single stop is repeated.
+fn linear_gradient(angle: u16, stops: &[String]) -> String {
+    let stops = if stops.len() == 1 {
+        format!("{0}, {0}", stops[0])
+    } else {
+        stops.join(", ")
+    };
+    format!("linear-gradient({angle}deg, {stops})")
+}
+
+fn css(rules: &Rules) -> String {
+    let block = |indent: &str, properties: &[(String, String)]| {
+        let mut block = format!("{indent}:root {{\n");
+        for (name, value) in properties {
+            block.push_str(&format!("{indent}  {name}: {value};\n"));
+        }
+        block.push_str(&format!("{indent}}}\n"));
+        block
+    };
+    let mut css = block("", &rules.root);
+    if let Some((condition, properties)) = &rules.other_scheme {
+        css.push_str(&format!("\n@media {condition} {{\n"));
+        css.push_str(&block("  ", properties));
+        css.push_str("}\n");
+    }
+    css
+}
+
+/// `--skyset-*` custom properties on `:root`. `skyset serve` answers
+/// `/theme.css` with this.
+pub struct Css;
+
+impl Exporter for Css {
+    fn name(&self) -> &str {
+        "css"
+    }
+
+    fn description(&self) -> &str {
+        "CSS custom properties (--skyset-*) on :root"
+    }
+
+    fn extension(&self) -> &str {
+        "css"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        Ok(css(&rules(skyset)))
+    }
+}
+
+/// `$skyset-*` variables for the file's palette, followed by the CSS
+/// custom properties (which follow `prefers-color-scheme` at runtime).
+pub struct Scss;
+
+impl Exporter for Scss {
+    fn name(&self) -> &str {
+        "scss"
+    }
+
+    fn description(&self) -> &str {
+        "SCSS $skyset-* variables plus the CSS custom properties"
+    }
+
+    fn extension(&self) -> &str {
+        "scss"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let rules = rules(skyset);
+        let mut scss = String::from("// Generated by `skyset export scss`.\n");
+        for (name, value) in &rules.root {
+            if let Some(name) = name.strip_prefix("--") {
+                scss.push_str(&format!("${name}: {value};\n"));
+            }
+        }
+        scss.push('\n');
+        scss.push_str(&css(&rules));
+        Ok(scss)
+    }
+}
+
+/// A Tailwind preset: `skyset-*` colors and background images backed by
+/// the custom properties, and a plugin that adds those to the base styles.
+fn tailwind_preset(skyset: &Skyset) -> Value {
+    let rules = rules(skyset);
+    let reference = |name: &str| Value::String(format!("var({name})"));
+    let mut colors = Map::new();
+    let mut images = Map::new();
+    for (name, _) in &rules.root {
+        let Some(key) = name.strip_prefix("--skyset-") else {
+            continue;
+        };
+        match key {
+            "mode" => {}
+            "background" | "hero" => {
+                images.insert(format!("skyset-{key}"), reference(name));
+            }
+            _ => {
+                colors.insert(key.to_string(), reference(name));
+            }
+        }
+    }
+
+    let declarations = |properties: &[(String, String)]| -> Map<String, Value> {
+        properties
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect()
+    };
+    let mut base = Map::new();
+    base.insert(":root".to_string(), declarations(&rules.root).into());
+    if let Some((condition, properties)) = &rules.other_scheme {
+        base.insert(
+            format!("@media {condition}"),
+            json!({ ":root": declarations(properties) }),
+        );
+    }
+
+    json!({
+        "theme": {
+            "extend": {
+                "colors": { "skyset": colors },
+                "backgroundImage": images,
+            }
+        },
+        "base": base,
+    })
+}
+
+/// `tailwind.config.js` preset with the base styles plugin built in.
+pub struct Tailwind;
+
+impl Exporter for Tailwind {
+    fn name(&self) -> &str {
+        "tailwind"
+    }
+
+    fn description(&self) -> &str {
+        "a Tailwind preset for `presets: [require('./skyset.js')]`"
+    }
+
+    fn extension(&self) -> &str {
+        "js"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let preset = tailwind_preset(skyset);
+        let pretty = |value: &Value| {
+            serde_json::to_string_pretty(value)
+                .map(|json| json.replace('\n', "\n  "))
+                .map_err(|err| SkysetError::Serialize(err.to_string()))
+        };
+        Ok(format!(
+            "// Generated by `skyset export tailwind`. In tailwind.config.js:\n//   presets: [require('./skyset.js')]\nmodule.exports = {{\n  theme: {},\n  plugins: [({{ addBase }}) => addBase({})],\n}};\n",
+            pretty(&preset["theme"])?,
+            pretty(&preset["base"])?,
+        ))
+    }
+}
+
+/// The same preset as JSON; `base` is meant for `addBase` in a plugin.
+pub struct TailwindJson;
+
+impl Exporter for TailwindJson {
+    fn name(&self) -> &str {
+        "tailwind-json"
+    }
+
+    fn description(&self) -> &str {
+        "the Tailwind preset as JSON (theme, plus base styles for addBase)"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        serde_json::to_string_pretty(&tailwind_preset(skyset))
+            .map(|json| json + "\n")
+            .map_err(|err| SkysetError::Serialize(err.to_string()))
+    }
+}
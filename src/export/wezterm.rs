@@ -0,0 +1,64 @@
+//! WezTerm: a Lua module with `colors` and a background gradient.
+
+use super::{Exporter, Roles};
+use crate::{error::SkysetError, model::Skyset};
+
+pub struct WezTerm;
+
+impl Exporter for WezTerm {
+    fn name(&self) -> &str {
+        "wezterm"
+    }
+
+    fn description(&self) -> &str {
+        "a WezTerm Lua module with colors and the background gradient"
+    }
+
+    fn extension(&self) -> &str {
+        "lua"
+    }
+
+    fn render(&self, skyset: &Skyset) -> Result<String, SkysetError> {
+        let roles = Roles::new(skyset);
+        let list = |colors: &[String]| {
+            colors
+                .iter()
+                .map(|color| format!("\"{color}\""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lua = String::from(
+            "-- Generated by `skyset export wezterm`. In wezterm.lua:\n--   local skyset = require 'skyset'\n--   config.colors = skyset.colors\n--   config.window_background_gradient = skyset.window_background_gradient\nreturn {\n  colors = {\n",
+        );
+        for (key, value) in [
+            ("background", &roles.surface),
+            ("foreground", &roles.foreground),
+            ("cursor_bg", &roles.accent),
+            ("cursor_border", &roles.accent),
+            ("cursor_fg", &roles.surface),
+            ("selection_bg", &roles.accent),
+            ("selection_fg", &roles.surface),
+            ("split", &roles.surface_overlay),
+        ] {
+            lua.push_str(&format!("    {key} = \"{value}\",\n"));
+        }
+        lua.push_str(&format!("    ansi = {{ {} }},\n", list(&roles.ansi[..8])));
+        lua.push_str(&format!(
+            "    brights = {{ {} }},\n",
+            list(&roles.ansi[8..])
+        ));
+        lua.push_str(&format!(
+            "    tab_bar = {{\n      background = \"{raised}\",\n      active_tab = {{ bg_color = \"{accent}\", fg_color = \"{surface}\" }},\n      inactive_tab = {{ bg_color = \"{raised}\", fg_color = \"{foreground}\" }},\n    }},\n",
+            raised = roles.surface_raised,
+            accent = roles.accent,
+            surface = roles.surface,
+            foreground = roles.foreground,
+        ));
+        lua.push_str("  },\n");
+        lua.push_str(&format!(
+            "  window_background_gradient = {{\n    orientation = \"Vertical\",\n    colors = {{ {} }},\n  }},\n}}\n",
+            list(&roles.background)
+        ));
+        Ok(lua)
+    }
+}
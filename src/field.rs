@@ -0,0 +1,255 @@
+use crate::{
+    color::{format_hex, parse_hex_rgb},
+    model::{Skyset, ThemeMode, theme_mode_label},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldId {
+    Origin,
+    Message,
+    Submessage,
+    ThemeMode,
+    Accent,
+    Primary,
+    Secondary,
+    Tertiary,
+    Background1,
+    Background2,
+    Background3,
+    Hero1,
+    Hero2,
+    SourceWillUpdate,
+}
+
+pub const FIELD_ORDER: [FieldId; 14] = [
+    FieldId::Origin,
+    FieldId::Message,
+    FieldId::Submessage,
+    FieldId::ThemeMode,
+    FieldId::Accent,
+    FieldId::Primary,
+    FieldId::Secondary,
+    FieldId::Tertiary,
+    FieldId::Background1,
+    FieldId::Background2,
+    FieldId::Background3,
+    FieldId::Hero1,
+    FieldId::Hero2,
+    FieldId::SourceWillUpdate,
+];
+
+impl FieldId {
+    pub fn label(self) -> &'static str {
+        match self {
+            FieldId::Origin => "Origin",
+            FieldId::Message => "Message",
+            FieldId::Submessage => "Submessage",
+            FieldId::ThemeMode => "Theme mode",
+            FieldId::Accent => "Accent",
+            FieldId::Primary => "Primary",
+            FieldId::Secondary => "Secondary",
+            FieldId::Tertiary => "Tertiary",
+            FieldId::Background1 => "Background #1",
+            FieldId::Background2 => "Background #2",
+            FieldId::Background3 => "Background #3",
+            FieldId::Hero1 => "Hero #1",
+            FieldId::Hero2 => "Hero #2",
+            FieldId::SourceWillUpdate => "Source will update",
+        }
+    }
+
+    /// Dotted path of the field in `latest.yml`, as used by `diff` and queries.
+    pub fn path(self) -> &'static str {
+        match self {
+            FieldId::Origin => "origin",
+            FieldId::Message => "message",
+            FieldId::Submessage => "submessage",
+            FieldId::ThemeMode => "theme.mode",
+            FieldId::Accent => "theme.accent",
+            FieldId::Primary => "palette.primary",
+            FieldId::Secondary => "palette.secondary",
+            FieldId::Tertiary => "palette.tertiary",
+            FieldId::Background1 => "gradients.background[0]",
+            FieldId::Background2 => "gradients.background[1]",
+            FieldId::Background3 => "gradients.background[2]",
+            FieldId::Hero1 => "gradients.hero[0]",
+            FieldId::Hero2 => "gradients.hero[1]",
+            FieldId::SourceWillUpdate => "source_will_update",
+        }
+    }
+
+    /// Short name matching the CLI override flag, e.g. `background1` for
+    /// `--background1`.
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldId::Origin => "origin",
+            FieldId::Message => "message",
+            FieldId::Submessage => "submessage",
+            FieldId::ThemeMode => "mode",
+            FieldId::Accent => "accent",
+            FieldId::Primary => "primary",
+            FieldId::Secondary => "secondary",
+            FieldId::Tertiary => "tertiary",
+            FieldId::Background1 => "background1",
+            FieldId::Background2 => "background2",
+            FieldId::Background3 => "background3",
+            FieldId::Hero1 => "hero1",
+            FieldId::Hero2 => "hero2",
+            FieldId::SourceWillUpdate => "source-will-update",
+        }
+    }
+
+    /// The accepted forms, for error messages.
+    pub fn expected(self) -> &'static str {
+        match self {
+            FieldId::Origin | FieldId::Message | FieldId::Submessage => "any text",
+            FieldId::ThemeMode => "dark, light, or system",
+            FieldId::SourceWillUpdate => "true or false (also yes/no, 1/0)",
+            _ => "a hex color like #RRGGBB or #RGB",
+        }
+    }
+
+    pub fn from_path(path: &str) -> Option<FieldId> {
+        FIELD_ORDER
+            .iter()
+            .copied()
+            .find(|field| field.path() == path)
+    }
+
+    /// Accepts either a dotted path or a short name.
+    pub fn from_key(key: &str) -> Option<FieldId> {
+        FieldId::from_path(key).or_else(|| {
+            FIELD_ORDER
+                .iter()
+                .copied()
+                .find(|field| field.name() == key)
+        })
+    }
+
+    pub fn is_toggle(self) -> bool {
+        matches!(self, FieldId::ThemeMode | FieldId::SourceWillUpdate)
+    }
+
+    pub fn is_color(self) -> bool {
+        !matches!(
+            self,
+            FieldId::Origin
+                | FieldId::Message
+                | FieldId::Submessage
+                | FieldId::ThemeMode
+                | FieldId::SourceWillUpdate
+        )
+    }
+}
+
+pub fn field_value(skyset: &Skyset, field: FieldId) -> String {
+    match field {
+        FieldId::Origin => skyset.origin.clone(),
+        FieldId::Message => skyset.message.clone(),
+        FieldId::Submessage => skyset.submessage.clone(),
+        FieldId::ThemeMode => theme_mode_label(skyset.theme.mode).to_string(),
+        FieldId::Accent => skyset.theme.accent.clone(),
+        FieldId::Primary => skyset.palette.primary.clone(),
+        FieldId::Secondary => skyset.palette.secondary.clone(),
+        FieldId::Tertiary => skyset.palette.tertiary.clone(),
+        FieldId::Background1 => skyset
+            .gradients
+            .background
+            .first()
+            .cloned()
+            .unwrap_or_default(),
+        FieldId::Background2 => skyset
+            .gradients
+            .background
+            .get(1)
+            .cloned()
+            .unwrap_or_default(),
+        FieldId::Background3 => skyset
+            .gradients
+            .background
+            .get(2)
+            .cloned()
+            .unwrap_or_default(),
+        FieldId::Hero1 => skyset.gradients.hero.first().cloned().unwrap_or_default(),
+        FieldId::Hero2 => skyset.gradients.hero.get(1).cloned().unwrap_or_default(),
+        FieldId::SourceWillUpdate => skyset.source_will_update.to_string(),
+    }
+}
+
+pub fn set_field_value(skyset: &mut Skyset, field: FieldId, value: String) -> Result<(), String> {
+    match field {
+        FieldId::Origin => skyset.origin = value,
+        FieldId::Message => skyset.message = value,
+        FieldId::Submessage => skyset.submessage = value,
+        FieldId::Accent => skyset.theme.accent = normalize_hex(&value)?,
+        FieldId::Primary => skyset.palette.primary = normalize_hex(&value)?,
+        FieldId::Secondary => skyset.palette.secondary = normalize_hex(&value)?,
+        FieldId::Tertiary => skyset.palette.tertiary = normalize_hex(&value)?,
+        FieldId::Background1 => set_gradient(&mut skyset.gradients.background, 0, value)?,
+        FieldId::Background2 => set_gradient(&mut skyset.gradients.background, 1, value)?,
+        FieldId::Background3 => set_gradient(&mut skyset.gradients.background, 2, value)?,
+        FieldId::Hero1 => set_gradient(&mut skyset.gradients.hero, 0, value)?,
+        FieldId::Hero2 => set_gradient(&mut skyset.gradients.hero, 1, value)?,
+        FieldId::ThemeMode => {
+            skyset.theme.mode = match value.to_lowercase().as_str() {
+                "dark" => ThemeMode::Dark,
+                "light" => ThemeMode::Light,
+                "system" => ThemeMode::System,
+                _ => return Err("Theme mode must be dark, light, or system".to_string()),
+            };
+        }
+        FieldId::SourceWillUpdate => {
+            skyset.source_will_update = match value.to_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => return Err("Source will update must be true or false".to_string()),
+            };
+        }
+    }
+    Ok(())
+}
+
+pub fn toggle_field(skyset: &mut Skyset, field: FieldId) {
+    match field {
+        FieldId::ThemeMode => {
+            skyset.theme.mode = match skyset.theme.mode {
+                ThemeMode::Dark => ThemeMode::Light,
+                ThemeMode::Light => ThemeMode::System,
+                ThemeMode::System | ThemeMode::Unknown => ThemeMode::Dark,
+            };
+        }
+        FieldId::SourceWillUpdate => {
+            skyset.source_will_update = !skyset.source_will_update;
+        }
+        _ => {}
+    }
+}
+
+/// Accepts whatever [`parse_hex_rgb`] does, returning `#RRGGBB`.
+pub fn normalize_hex(value: &str) -> Result<String, String> {
+    parse_hex_rgb(value)
+        .map(|(r, g, b)| format_hex(r, g, b))
+        .ok_or_else(|| "Invalid hex color".to_string())
+}
+
+fn set_gradient(gradient: &mut Vec<String>, index: usize, value: String) -> Result<(), String> {
+    let normalized = normalize_hex(&value)?;
+    if gradient.len() <= index {
+        gradient.resize(index + 1, "#000000".to_string());
+    }
+    gradient[index] = normalized;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_hex_formats() {
+        assert_eq!(normalize_hex("#abcdef").unwrap(), "#ABCDEF");
+        assert_eq!(normalize_hex("123456").unwrap(), "#123456");
+        assert_eq!(normalize_hex("#fa0").unwrap(), "#FFAA00");
+        assert!(normalize_hex("#12345").is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! A rolling archive of previous versions of `latest.yml`.
+//!
+//! Before `io::write_skyset` replaces the file, the text it is about to
+//! overwrite is copied to `history/<timestamp>-<origin>.yml` next to it,
+//! where the timestamp is the old `updated_at` (with `.1`, `.2`, ... before
+//! `.yml` for later versions with the same timestamp and origin). The oldest
+//! entries are pruned once there are more than [`Limits::max_entries`] of
+//! them or they take more than [`Limits::max_bytes`]. A version that cannot
+//! be archived is noted in `history.log` and does not stop the write.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+
+use crate::{error::SkysetError, io, model::Skyset, roundtrip};
+
+/// Entry names start with the timestamp in this format, e.g.
+/// `20260301T083000Z`. Origins never contain `.`, which marks a sequence.
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const STAMP_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_entries: usize,
+    pub max_bytes: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_entries: 100,
+            max_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// One archived version. Its text is only read by [`Entry::load`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// File name, which is also what `restore` and `show` accept.
+    pub id: String,
+    pub path: PathBuf,
+}
+
+impl Entry {
+    /// The archived values, or `None` if the file no longer reads or parses.
+    pub fn load(&self) -> Option<Skyset> {
+        let text = fs::read_to_string(&self.path).ok()?;
+        io::parse_skyset(&self.path, &text).ok()
+    }
+}
+
+/// `history/` in the same directory as `config_path`.
+pub fn history_dir(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("history")
+}
+
+/// `history.log` in the same directory as `config_path`.
+pub fn log_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("history.log")
+}
+
+/// [`archive`] with the default limits, for a write that goes ahead either
+/// way: a failure is appended to [`log_path`] instead of returned.
+pub fn archive_or_log(config_path: &Path, previous: &str) {
+    let Err(err) = archive(config_path, previous, Limits::default()) else {
+        return;
+    };
+    if let Ok(mut log) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(config_path))
+    {
+        let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        let _ = writeln!(log, "{now} could not archive the previous version: {err}");
+    }
+}
+
+/// Copies `previous` (the current contents of `config_path`) into the
+/// archive and prunes it. Empty text is not archived. Returns the new entry.
+pub fn archive(
+    config_path: &Path,
+    previous: &str,
+    limits: Limits,
+) -> Result<Option<PathBuf>, SkysetError> {
+    if previous.trim().is_empty() {
+        return Ok(None);
+    }
+    let dir = history_dir(config_path);
+    let io_error = |err| SkysetError::from_io(dir.clone(), err);
+    fs::create_dir_all(&dir).map_err(io_error)?;
+
+    let skyset = io::parse_skyset(config_path, previous).ok();
+    let stem = entry_stem(skyset.as_ref());
+    let mut paths = entry_paths(&dir)?;
+    // Later versions with the same name get the next sequence number, even
+    // if the entries before them have been pruned.
+    let sequence = paths
+        .iter()
+        .filter_map(|path| entry_sequence(&file_name(path), &stem))
+        .map(|sequence| sequence + 1)
+        .max();
+    let path = match sequence {
+        Some(sequence) => dir.join(format!("{stem}.{sequence}.yml")),
+        None => dir.join(format!("{stem}.yml")),
+    };
+    fs::write(&path, previous).map_err(io_error)?;
+    let at = paths.partition_point(|other| entry_order(other) <= entry_order(&path));
+    paths.insert(at, path.clone());
+    prune_paths(&paths, limits)?;
+    Ok(Some(path))
+}
+
+/// Archived versions, newest first.
+pub fn list(config_path: &Path) -> Result<Vec<Entry>, SkysetError> {
+    let entries: Vec<_> = entry_paths(&history_dir(config_path))?
+        .into_iter()
+        .rev()
+        .map(|path| Entry {
+            id: file_name(&path),
+            path,
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Finds an entry by id, or by its 1-based position in [`list`].
+pub fn find(config_path: &Path, id: &str) -> Result<Entry, SkysetError> {
+    let entries = list(config_path)?;
+    let by_position = id
+        .parse::<usize>()
+        .ok()
+        .and_then(|position| position.checked_sub(1))
+        .and_then(|idx| entries.get(idx));
+    by_position
+        .or_else(|| {
+            entries
+                .iter()
+                .find(|entry| entry.id == id || entry.id.trim_end_matches(".yml") == id)
+        })
+        .cloned()
+        .ok_or_else(|| SkysetError::NotFound(history_dir(config_path).join(id)))
+}
+
+/// Writes `entry` back over `config_path` with a fresh `updated_at`,
+/// archiving the version it replaces. Returns the restored values.
+pub fn restore(config_path: &Path, entry: &Entry) -> Result<Skyset, SkysetError> {
+    let raw = fs::read_to_string(&entry.path)
+        .map_err(|err| SkysetError::from_io(entry.path.clone(), err))?;
+    let mut skyset = io::parse_skyset(&entry.path, &raw)?;
+    io::prepare_for_save(&mut skyset);
+    let serialized = roundtrip::render(&raw, &skyset)?;
+    io::write_skyset(config_path, &serialized)?;
+    Ok(skyset)
+}
+
+/// Drops the oldest entries in `dir` until it is within `limits`. The
+/// newest entry is always kept.
+pub fn prune(dir: &Path, limits: Limits) -> Result<(), SkysetError> {
+    prune_paths(&entry_paths(dir)?, limits)
+}
+
+/// [`prune`] over `paths`, which are ordered like [`entry_paths`].
+fn prune_paths(paths: &[PathBuf], limits: Limits) -> Result<(), SkysetError> {
+    let sizes: Vec<u64> = paths
+        .iter()
+        .map(|path| fs::metadata(path).map(|meta| meta.len()).unwrap_or(0))
+        .collect();
+    let mut count = paths.len();
+    let mut total: u64 = sizes.iter().sum();
+    for (path, size) in paths.iter().zip(&sizes) {
+        if count <= 1 || (count <= limits.max_entries && total <= limits.max_bytes) {
+            break;
+        }
+        fs::remove_file(path).map_err(|err| SkysetError::from_io(path.clone(), err))?;
+        count -= 1;
+        total -= size;
+    }
+    Ok(())
+}
+
+/// `.yml` files in `dir`, oldest first: by the timestamp and sequence in
+/// their names. Names that do not have them come first.
+fn entry_paths(dir: &Path) -> Result<Vec<PathBuf>, SkysetError> {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(SkysetError::from_io(dir.to_path_buf(), err)),
+    };
+    let mut paths: Vec<_> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yml"))
+        .map(|path| (entry_order(&path), path))
+        .collect();
+    paths.sort();
+    Ok(paths.into_iter().map(|(_, path)| path).collect())
+}
+
+/// `(timestamp, sequence)` from `<timestamp>-<origin>[.<sequence>].yml`.
+fn entry_order(path: &Path) -> Option<(NaiveDateTime, u32)> {
+    let stem = path.file_stem()?.to_str()?;
+    let (base, sequence) = match stem.split_once('.') {
+        Some((base, sequence)) => (base, sequence.parse().ok()?),
+        None => (stem, 0),
+    };
+    let stamp = NaiveDateTime::parse_from_str(base.get(..STAMP_LEN)?, STAMP_FORMAT).ok()?;
+    Some((stamp, sequence))
+}
+
+/// The sequence in `name` if it is `<stem>.yml` (0) or `<stem>.<n>.yml`.
+fn entry_sequence(name: &str, stem: &str) -> Option<u32> {
+    match name.strip_prefix(stem)?.strip_suffix(".yml")? {
+        "" => Some(0),
+        rest => rest.strip_prefix('.')?.parse().ok(),
+    }
+}
+
+fn entry_stem(skyset: Option<&Skyset>) -> String {
+    let stamp = skyset
+        .and_then(|skyset| DateTime::parse_from_rfc3339(&skyset.updated_at).ok())
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+    let origin: String = skyset
+        .map(|skyset| skyset.origin.as_str())
+        .filter(|origin| !origin.trim().is_empty())
+        .unwrap_or("unknown")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(40)
+        .collect();
+    format!("{}-{origin}", stamp.format(STAMP_FORMAT))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn history_archives_prunes_and_restores() {
+        let dir = TempDir::new("history");
+        let path = dir.join("latest.yml");
+        for message in ["v1", "v2", "v3", "v4"] {
+            let mut skyset = Skyset {
+                message: message.to_string(),
+                ..Skyset::default()
+            };
+            io::save_skyset(&path, &mut skyset).unwrap();
+        }
+        let messages = |entries: &[Entry]| -> Vec<String> {
+            entries
+                .iter()
+                .map(|entry| entry.load().unwrap().message)
+                .collect()
+        };
+        let archived = list(&path).unwrap();
+        assert_eq!(messages(&archived), ["v3", "v2", "v1"]);
+
+        let limits = Limits {
+            max_entries: 2,
+            ..Limits::default()
+        };
+        prune(&history_dir(&path), limits).unwrap();
+        assert_eq!(messages(&list(&path).unwrap()), ["v3", "v2"]);
+
+        let entry = find(&path, "2").unwrap();
+        let restored = restore(&path, &entry).unwrap();
+        let on_disk = io::load_skyset(&path).unwrap();
+        let after = list(&path).unwrap();
+
+        assert_eq!(restored.message, "v2");
+        assert_eq!(on_disk, restored);
+        assert_eq!(messages(&after)[0], "v4");
+    }
+
+    #[test]
+    fn history_orders_versions_from_the_same_second() {
+        let dir = TempDir::new("history-seq");
+        let path = dir.join("latest.yml");
+        let mut archived = Vec::new();
+        for message in ["v1", "v2", "v3"] {
+            let text = format!(
+                "updated_at: \"2026-03-01T08:30:00Z\"\norigin: weather-2\nmessage: {message}\n"
+            );
+            archived.push(archive(&path, &text, Limits::default()).unwrap());
+        }
+        // Newest written first, so modification times cannot be what orders them.
+        for (idx, entry) in archived.iter().flatten().rev().enumerate() {
+            let modified = UNIX_EPOCH + Duration::from_secs(idx as u64);
+            std::fs::File::options()
+                .write(true)
+                .open(entry)
+                .and_then(|file| file.set_modified(modified))
+                .unwrap();
+        }
+
+        let entries = list(&path).unwrap();
+
+        let ids: Vec<_> = entries.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "20260301T083000Z-weather-2.2.yml",
+                "20260301T083000Z-weather-2.1.yml",
+                "20260301T083000Z-weather-2.yml",
+            ]
+        );
+        assert_eq!(entries[0].load().unwrap().message, "v3");
+    }
+
+    #[test]
+    fn write_goes_ahead_when_archiving_fails() {
+        let dir = TempDir::new("archive-fail");
+        let path = dir.join("latest.yml");
+        io::write_skyset(&path, "message: before\n").unwrap();
+        // A file where the archive directory should be.
+        std::fs::write(history_dir(&path), "").unwrap();
+
+        io::write_skyset(&path, "message: after\n").unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        let log = std::fs::read_to_string(log_path(&path)).unwrap_or_default();
+
+        assert_eq!(written, "message: after\n");
+        assert!(log.contains("could not archive the previous version"));
+    }
+}
@@ -0,0 +1,50 @@
+use skyset::{history::Entry, model::Skyset};
+
+/// Browsing state for the archived versions, newest first. Only the
+/// selected version is read from disk.
+pub struct HistoryPane {
+    entries: Vec<Entry>,
+    selected: usize,
+    current: Option<Skyset>,
+}
+
+impl HistoryPane {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        let current = entries.first().and_then(Entry::load);
+        Self {
+            entries,
+            selected: 0,
+            current,
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The selected version, if it still parses.
+    pub fn current(&self) -> Option<&Skyset> {
+        self.current.as_ref()
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.select(self.selected + 1);
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.select(self.selected.saturating_sub(1));
+    }
+
+    fn select(&mut self, idx: usize) {
+        if idx != self.selected {
+            self.selected = idx;
+            self.current = self.entries.get(idx).and_then(Entry::load);
+        }
+    }
+}
@@ -0,0 +1,493 @@
+//! Commands to run whenever the theme changes.
+//!
+//! Hooks are listed in `hooks.yml` next to `latest.yml`:
+//!
+//! ```yaml
+//! max_concurrent: 4        # hooks running at once (default 4)
+//! on_save: true            # also run after saving from the TUI (default true)
+//! log: hooks.log           # failure log, relative to this file (default hooks.log)
+//! hooks:
+//!   - name: kitty
+//!     command: kitty @ set-colors --all background=$SKYSET_PRIMARY
+//!     timeout_secs: 5      # default 10
+//! ```
+//!
+//! Each command runs through the shell, in the directory of `hooks.yml`, with the new values in `SKYSET_*`
+//! environment variables (`SKYSET_ACCENT`, `SKYSET_PRIMARY`, `SKYSET_MODE`,
+//! ...) and the whole `Skyset` as JSON on stdin. Failures and timeouts are
+//! appended to the log; they never stop the other hooks.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::SkysetError,
+    field::{FIELD_ORDER, field_value},
+    model::Skyset,
+};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_MAX_CONCURRENT: usize = 4;
+const WAIT_STEP: Duration = Duration::from_millis(20);
+const STDERR_GRACE: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub max_concurrent: usize,
+    pub on_save: bool,
+    pub log: PathBuf,
+    pub hooks: Vec<Hook>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            on_save: true,
+            log: PathBuf::from("hooks.log"),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hook {
+    pub name: String,
+    pub command: String,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookStatus {
+    Succeeded,
+    /// Exited non-zero (`None` if killed by a signal), with its stderr.
+    Failed {
+        code: Option<i32>,
+        stderr: String,
+    },
+    TimedOut,
+    SpawnFailed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookOutcome {
+    pub name: String,
+    pub status: HookStatus,
+}
+
+impl HookOutcome {
+    /// Why the hook failed, or `None` if it succeeded.
+    pub fn failure(&self) -> Option<String> {
+        match &self.status {
+            HookStatus::Succeeded => None,
+            HookStatus::Failed { code, stderr } => {
+                let code = code.map_or("a signal".to_string(), |code| format!("status {code}"));
+                if stderr.is_empty() {
+                    Some(format!("failed with {code}"))
+                } else {
+                    Some(format!("failed with {code}: {stderr}"))
+                }
+            }
+            HookStatus::TimedOut => Some("timed out and was killed".to_string()),
+            HookStatus::SpawnFailed(err) => Some(format!("could not start: {err}")),
+        }
+    }
+}
+
+/// `hooks.yml` in the same directory as `config_path`.
+pub fn hooks_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("hooks.yml")
+}
+
+impl HooksConfig {
+    /// Reads `path`; a missing file means no hooks.
+    pub fn load(path: &Path) -> Result<Self, SkysetError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(SkysetError::from_io(path.to_path_buf(), err)),
+        };
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let mut config: Self = serde_yaml::from_str(&content)
+            .map_err(|err| SkysetError::from_yaml(path.to_path_buf(), err))?;
+        if let Some(dir) = path.parent() {
+            config.log = dir.join(&config.log);
+        }
+        Ok(config)
+    }
+
+    /// Runs every hook for `skyset`, at most `max_concurrent` at a time, and
+    /// waits for them. Failures are appended to the log.
+    pub fn run(&self, config_path: &Path, skyset: &Skyset) -> Vec<HookOutcome> {
+        let env = env_vars(config_path, skyset);
+        let dir = config_path.parent().unwrap_or(Path::new("."));
+        let stdin = serde_json::to_string(skyset).unwrap_or_default();
+        let next = AtomicUsize::new(0);
+        let workers = self.max_concurrent.clamp(1, self.hooks.len().max(1));
+
+        let mut outcomes: Vec<(usize, HookOutcome)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let idx = next.fetch_add(1, Ordering::SeqCst);
+                            let Some(hook) = self.hooks.get(idx) else {
+                                return done;
+                            };
+                            done.push((idx, run_hook(hook, dir, &env, &stdin)));
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        });
+        outcomes.sort_by_key(|(idx, _)| *idx);
+        let outcomes: Vec<_> = outcomes.into_iter().map(|(_, outcome)| outcome).collect();
+
+        self.log_failures(&outcomes);
+        outcomes
+    }
+
+    fn log_failures(&self, outcomes: &[HookOutcome]) {
+        let failures: Vec<_> = outcomes
+            .iter()
+            .filter_map(|outcome| outcome.failure().map(|reason| (outcome, reason)))
+            .collect();
+        if failures.is_empty() {
+            return;
+        }
+        for (outcome, reason) in failures {
+            self.log(&format!("hook {:?} {reason}", outcome.name));
+        }
+    }
+
+    /// Appends a timestamped line to the failure log.
+    pub fn log(&self, message: &str) {
+        if let Ok(mut log) = OpenOptions::new().create(true).append(true).open(&self.log) {
+            let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+            let _ = writeln!(log, "{now} {message}");
+        }
+    }
+}
+
+/// Loads the hooks next to `config_path` and runs them for a save, unless
+/// `on_save` is off. A `hooks.yml` that cannot be read is logged to the
+/// default `hooks.log`.
+pub fn run_on_save(config_path: &Path, skyset: &Skyset) {
+    let path = hooks_path(config_path);
+    match HooksConfig::load(&path) {
+        Ok(config) if config.on_save => {
+            config.run(config_path, skyset);
+        }
+        Ok(_) => {}
+        Err(err) => {
+            let fallback = HooksConfig {
+                log: config_path.with_file_name("hooks.log"),
+                ..HooksConfig::default()
+            };
+            fallback.log(&err.to_string());
+        }
+    }
+}
+
+/// Runs the hooks for saves on one background thread, so saves in quick
+/// succession share `max_concurrent` instead of each starting a batch of
+/// their own. Saves queued while hooks run collapse into the newest one.
+pub struct HookRunner {
+    queue: Option<mpsc::Sender<(PathBuf, Skyset)>>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl HookRunner {
+    pub fn new() -> Self {
+        let (queue, saves) = mpsc::channel::<(PathBuf, Skyset)>();
+        let worker = thread::spawn(move || {
+            while let Ok(mut save) = saves.recv() {
+                while let Ok(newer) = saves.try_recv() {
+                    save = newer;
+                }
+                run_on_save(&save.0, &save.1);
+            }
+        });
+        Self {
+            queue: Some(queue),
+            worker: Some(worker),
+        }
+    }
+
+    /// Queues [`run_on_save`] for `skyset`, just written to `config_path`.
+    pub fn on_save(&self, config_path: &Path, skyset: &Skyset) {
+        if let Some(queue) = &self.queue {
+            let _ = queue.send((config_path.to_path_buf(), skyset.clone()));
+        }
+    }
+
+    /// Waits for the queued and running hooks. Dropping the runner does the
+    /// same.
+    pub fn finish(&mut self) {
+        self.queue.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Default for HookRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for HookRunner {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// `SKYSET_<NAME>` for every editor field (e.g. `SKYSET_BACKGROUND1`,
+/// `SKYSET_SOURCE_WILL_UPDATE`), plus `SKYSET_UPDATED_AT` and `SKYSET_PATH`.
+pub fn env_vars(config_path: &Path, skyset: &Skyset) -> Vec<(String, String)> {
+    let mut vars: Vec<_> = FIELD_ORDER
+        .iter()
+        .map(|field| {
+            let name = field.name().to_uppercase().replace('-', "_");
+            (format!("SKYSET_{name}"), field_value(skyset, *field))
+        })
+        .collect();
+    vars.push(("SKYSET_UPDATED_AT".to_string(), skyset.updated_at.clone()));
+    vars.push(("SKYSET_PATH".to_string(), config_path.display().to_string()));
+    vars
+}
+
+fn run_hook(hook: &Hook, dir: &Path, env: &[(String, String)], stdin: &str) -> HookOutcome {
+    let outcome = |status| HookOutcome {
+        name: hook.name.clone(),
+        status,
+    };
+
+    let mut child = match shell(&hook.command)
+        .current_dir(dir)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => return outcome(HookStatus::SpawnFailed(err.to_string())),
+    };
+
+    // Written from its own thread so a hook that never reads stdin still
+    // runs into the timeout; hooks that exit before reading it are fine.
+    if let Some(mut pipe) = child.stdin.take() {
+        let stdin = stdin.to_string();
+        thread::spawn(move || {
+            let _ = pipe.write_all(stdin.as_bytes());
+        });
+    }
+    // Background processes started by the hook can hold stderr open long
+    // after it exits, so the reader reports back over a channel and is
+    // abandoned if it does not finish shortly after the hook.
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    if let Some(mut pipe) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut text = String::new();
+            let _ = pipe.read_to_string(&mut text);
+            let _ = stderr_tx.send(text);
+        });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(hook.timeout_secs);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() >= deadline => {
+                kill(&mut child);
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => thread::sleep(WAIT_STEP),
+            Err(err) => return outcome(HookStatus::SpawnFailed(err.to_string())),
+        }
+    };
+    let stderr = stderr_rx.recv_timeout(STDERR_GRACE).unwrap_or_default();
+
+    outcome(match status {
+        None => HookStatus::TimedOut,
+        Some(status) if status.success() => HookStatus::Succeeded,
+        Some(status) => HookStatus::Failed {
+            code: status.code(),
+            stderr: stderr.trim().to_string(),
+        },
+    })
+}
+
+/// Each hook leads its own process group, so a timeout also kills what it
+/// started in the background.
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    use std::os::unix::process::CommandExt;
+
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command).process_group(0);
+    shell
+}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    match libc::pid_t::try_from(child.id()) {
+        // SAFETY: `kill` has no memory-safety preconditions; the group is
+        // the hook's own (see `shell`) and it has not been reaped yet.
+        Ok(pid) => unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        },
+        Err(_) => {
+            let _ = child.kill();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn hooks_get_env_and_stdin_and_log_failures() {
+        let dir = TempDir::new("hooks");
+        let config_path = dir.join("latest.yml");
+        std::fs::write(
+            hooks_path(&config_path),
+            "max_concurrent: 2\nhooks:\n  - name: capture\n    command: printf %s \"$SKYSET_ACCENT\" > env.out; cat > stdin.out\n  - name: broken\n    command: echo nope >&2; exit 3\n  - name: slow\n    command: sleep 5\n    timeout_secs: 1\n",
+        )
+        .unwrap();
+
+        let config = HooksConfig::load(&hooks_path(&config_path)).unwrap();
+        let skyset = Skyset::default();
+        let outcomes = config.run(&config_path, &skyset);
+        let env_out = std::fs::read_to_string(dir.join("env.out")).unwrap_or_default();
+        let stdin_out = std::fs::read_to_string(dir.join("stdin.out")).unwrap_or_default();
+        let log = std::fs::read_to_string(dir.join("hooks.log")).unwrap_or_default();
+
+        assert_eq!(outcomes[0].status, HookStatus::Succeeded);
+        assert_eq!(env_out, skyset.theme.accent);
+        assert!(stdin_out.contains("\"accent\""));
+        assert_eq!(
+            outcomes[1].status,
+            HookStatus::Failed {
+                code: Some(3),
+                stderr: "nope".to_string()
+            }
+        );
+        assert_eq!(outcomes[2].status, HookStatus::TimedOut);
+        assert!(log.contains("\"broken\" failed with status 3: nope"));
+        assert!(log.contains("\"slow\" timed out"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hooks_timeout_kills_background_jobs() {
+        let dir = TempDir::new("hook-group");
+        let config = HooksConfig {
+            log: dir.join("hooks.log"),
+            hooks: vec![Hook {
+                name: "forks".to_string(),
+                command: "(sleep 2; touch late) & wait".to_string(),
+                timeout_secs: 1,
+            }],
+            ..Default::default()
+        };
+
+        let outcomes = config.run(&dir.join("latest.yml"), &Skyset::default());
+        std::thread::sleep(Duration::from_millis(1500));
+        let survived = dir.join("late").exists();
+
+        assert_eq!(outcomes[0].status, HookStatus::TimedOut);
+        assert!(!survived, "the background job outlived the timeout");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hooks_that_ignore_stdin_still_time_out() {
+        let dir = TempDir::new("hook-stdin");
+        let hook = Hook {
+            name: "deaf".to_string(),
+            command: "sleep 5".to_string(),
+            timeout_secs: 1,
+        };
+        // Far more than a pipe buffer holds.
+        let stdin = "x".repeat(1 << 20);
+
+        let started = Instant::now();
+        let outcome = run_hook(&hook, dir.path(), &[], &stdin);
+
+        assert_eq!(outcome.status, HookStatus::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_runner_finishes_queued_saves() {
+        let dir = TempDir::new("hook-runner");
+        let config_path = dir.join("latest.yml");
+        std::fs::write(
+            hooks_path(&config_path),
+            "hooks:\n  - name: record\n    command: sleep 0.2; echo \"$SKYSET_ACCENT\" >> accents\n",
+        )
+        .unwrap();
+
+        let mut runner = HookRunner::new();
+        let mut skyset = Skyset::default();
+        skyset.theme.accent = "#111111".to_string();
+        runner.on_save(&config_path, &skyset);
+        // The first hook is still sleeping while the next two saves queue up,
+        // so only the newest of them runs.
+        thread::sleep(Duration::from_millis(50));
+        for accent in ["#222222", "#333333"] {
+            skyset.theme.accent = accent.to_string();
+            runner.on_save(&config_path, &skyset);
+        }
+        runner.finish();
+        let accents = std::fs::read_to_string(dir.join("accents")).unwrap_or_default();
+
+        assert_eq!(accents.lines().collect::<Vec<_>>(), ["#111111", "#333333"]);
+    }
+}
@@ -1,55 +1,195 @@
-use std::{fs, io, path::Path};
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
-use crate::model::Skyset;
+use chrono::{SecondsFormat, Utc};
+
+use crate::{
+    error::SkysetError,
+    history,
+    model::{SCHEMA_VERSION, Skyset},
+    roundtrip,
+};
 
 pub struct ReadOutcome {
     pub skyset: Skyset,
     pub raw: String,
+    pub stamp: Option<DiskStamp>,
+}
+
+/// Identifies the version of the file we last read or wrote, so a save can
+/// tell whether another producer has replaced it since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskStamp {
+    pub modified: Option<SystemTime>,
+    pub hash: u64,
 }
 
-pub fn load_initial_state(path: &Path) -> ReadOutcome {
+impl DiskStamp {
+    pub fn new(path: &Path, contents: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        Self {
+            modified: fs::metadata(path).and_then(|meta| meta.modified()).ok(),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+/// Stamp of whatever is on disk right now, or `None` if it cannot be read.
+pub fn current_stamp(path: &Path) -> Option<DiskStamp> {
+    let contents = fs::read_to_string(path).ok()?;
+    Some(DiskStamp::new(path, &contents))
+}
+
+/// Result of the most recent attempt to read `latest.yml`.
+pub enum ReadStatus {
+    Loaded,
+    Empty,
+    Missing,
+    Failed(SkysetError),
+}
+
+impl ReadStatus {
+    pub fn is_error(&self) -> bool {
+        matches!(self, ReadStatus::Failed(_))
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ReadStatus::Loaded => "loaded".to_string(),
+            ReadStatus::Empty => "empty file (no signal)".to_string(),
+            ReadStatus::Missing => "missing file (no signal)".to_string(),
+            ReadStatus::Failed(err) => err.to_string(),
+        }
+    }
+}
+
+pub fn load_initial_state(path: &Path) -> (ReadOutcome, ReadStatus) {
     match read_skyset(path) {
-        Ok(outcome) => outcome,
-        Err(_err) => ReadOutcome {
-            skyset: Skyset::default(),
-            raw: String::new(),
-        },
+        Ok(outcome) => {
+            let status = status_for(&outcome);
+            (outcome, status)
+        }
+        Err(err) => {
+            let status = match err {
+                SkysetError::NotFound(_) => ReadStatus::Missing,
+                err => ReadStatus::Failed(err),
+            };
+            let outcome = ReadOutcome {
+                skyset: Skyset::default(),
+                raw: String::new(),
+                stamp: None,
+            };
+            (outcome, status)
+        }
+    }
+}
+
+pub fn status_for(outcome: &ReadOutcome) -> ReadStatus {
+    if outcome.raw.is_empty() {
+        ReadStatus::Empty
+    } else {
+        ReadStatus::Loaded
     }
 }
 
-pub fn read_skyset(path: &Path) -> io::Result<ReadOutcome> {
-    let content = match fs::read_to_string(path) {
-        Ok(text) => text,
-        Err(err) if err.kind() == io::ErrorKind::NotFound => return Err(err),
-        Err(err) => return Err(err),
+pub fn read_skyset(path: &Path) -> Result<ReadOutcome, SkysetError> {
+    let content =
+        fs::read_to_string(path).map_err(|err| SkysetError::from_io(path.to_path_buf(), err))?;
+
+    let stamp = Some(DiskStamp::new(path, &content));
+    let skyset = parse_skyset(path, &content)?;
+    let raw = if content.trim().is_empty() {
+        String::new()
+    } else {
+        content
     };
+    Ok(ReadOutcome { skyset, raw, stamp })
+}
 
+/// Parses the contents of `path`; an empty document means defaults.
+pub fn parse_skyset(path: &Path, content: &str) -> Result<Skyset, SkysetError> {
     if content.trim().is_empty() {
-        return Ok(ReadOutcome {
-            skyset: Skyset::default(),
-            raw: String::new(),
-        });
+        return Ok(Skyset::default());
     }
+    serde_yaml::from_str::<Skyset>(content)
+        .map_err(|err| SkysetError::from_yaml(path.to_path_buf(), err))
+}
 
-    match serde_yaml::from_str::<Skyset>(&content) {
-        Ok(skyset) => Ok(ReadOutcome {
-            skyset,
-            raw: content,
-        }),
-        Err(_err) => Ok(ReadOutcome {
-            skyset: Skyset::default(),
-            raw: content,
-        }),
+/// Reads `path` and returns just the parsed `Skyset`.
+pub fn load_skyset(path: &Path) -> Result<Skyset, SkysetError> {
+    read_skyset(path).map(|outcome| outcome.skyset)
+}
+
+/// Stamps `skyset` for saving and atomically writes it over `path`, keeping
+/// unknown keys, comments and key order from the file it replaces. Returns
+/// the text that was written.
+pub fn save_skyset(path: &Path, skyset: &mut Skyset) -> Result<String, SkysetError> {
+    prepare_for_save(skyset);
+    let previous = fs::read_to_string(path).unwrap_or_default();
+    let serialized = roundtrip::render(&previous, skyset)?;
+    write_skyset(path, &serialized)?;
+    Ok(serialized)
+}
+
+/// Sets `updated_at` to now (UTC RFC3339) and `_version` to the current schema.
+pub fn prepare_for_save(skyset: &mut Skyset) {
+    skyset.updated_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    skyset.version = SCHEMA_VERSION;
+}
+
+/// Atomically replaces `path` with `contents`, first copying the version it
+/// replaces into the `history` archive (see [`history::archive_or_log`]).
+pub fn write_skyset(path: &Path, contents: &str) -> Result<(), SkysetError> {
+    if let Ok(previous) = fs::read_to_string(path)
+        && previous != contents
+    {
+        history::archive_or_log(path, &previous);
     }
+    write_atomic(path, contents)
 }
 
-pub fn write_skyset(path: &Path, contents: &str) -> io::Result<()> {
+/// Writes `contents` to a temp file next to `path`, then renames it into
+/// place, so readers never see a half-written file.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), SkysetError> {
+    let io_error = |err| SkysetError::from_io(path.to_path_buf(), err);
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        fs::create_dir_all(parent).map_err(io_error)?;
     }
 
-    let tmp_path = path.with_extension("yml.tmp");
-    fs::write(&tmp_path, contents)?;
-    fs::rename(&tmp_path, path)?;
+    let tmp_path = temp_path(path);
+    fs::write(&tmp_path, contents).map_err(io_error)?;
+    fs::rename(&tmp_path, path).map_err(io_error)?;
     Ok(())
 }
+
+/// Sibling file that writes go to before being renamed over `path`.
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    path.with_extension("yml.tmp")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn parse_errors_keep_location() {
+        let dir = TempDir::new("parse");
+        let path = dir.join("latest.yml");
+        std::fs::write(&path, "message: ok\ntheme: [unclosed\n").unwrap();
+
+        let result = read_skyset(&path);
+        match result {
+            Err(SkysetError::Parse { line, column, .. }) => {
+                assert!(line.is_some());
+                assert!(column.is_some());
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Reference implementation of the Skyset file format.
+//!
+//! One producer writes `~/.config/skyset/latest.yml`; any number of
+//! consumers read it. This crate holds the schema ([`model`]), color and
+//! path helpers, and the read/write API, so consumers do not have to
+//! re-implement them:
+//!
+//! ```no_run
+//! use skyset::{io, paths};
+//!
+//! let path = paths::default_config_path();
+//! let skyset = io::load_skyset(&path)?;
+//! println!("accent is {}", skyset.theme.accent);
+//! # Ok::<(), skyset::SkysetError>(())
+//! ```
+//!
+//! The supported modules are:
+//!
+//! - [`model`], [`error`], [`paths`], [`color`] and [`field`]: the schema
+//!   and its helpers
+//! - [`io`] and [`roundtrip`]: reading, and writing without losing
+//!   comments or unknown keys
+//! - [`validate`], [`diff`], [`merge`] and [`query`]: checking, comparing,
+//!   patching and looking up values
+//! - [`watch`] and, on Unix, [`daemon`]: following changes
+//! - [`export`]: rendering the theme for other programs
+//! - `async_io`, with the `tokio` feature
+//!
+//! `hooks`, `serve`, `terminal` and `history` back the `skyset` binary's
+//! subcommands. They are public only so the binary can use them and may
+//! change without notice.
+//!
+//! The TUI editor is the `skyset` binary, built with the default `tui`
+//! feature. Depend on this crate with `default-features = false` to leave
+//! out ratatui and crossterm.
+
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod color;
+#[cfg(unix)]
+pub mod daemon;
+pub mod diff;
+pub mod error;
+pub mod export;
+pub mod field;
+#[doc(hidden)]
+pub mod history;
+#[doc(hidden)]
+pub mod hooks;
+pub mod io;
+pub mod merge;
+pub mod model;
+pub mod paths;
+pub mod query;
+pub mod roundtrip;
+#[doc(hidden)]
+pub mod serve;
+#[doc(hidden)]
+pub mod terminal;
+pub mod validate;
+pub mod watch;
+
+pub use error::SkysetError;
+pub use model::{Gradients, Palette, Skyset, Theme, ThemeMode};
+
+#[cfg(test)]
+mod test_support;
@@ -1,15 +1,19 @@
 mod app;
 mod cli;
-mod color;
+mod commands;
+mod conflict;
 mod editor;
-mod io;
-mod model;
+mod history_pane;
 mod output;
-mod paths;
+mod picker;
+#[cfg(test)]
+mod test_support;
 mod ui;
+mod undo;
 
 use std::{
     io::{self as stdio, Stdout},
+    process::ExitCode,
     time::Duration,
 };
 
@@ -19,36 +23,63 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
+use skyset::paths::normalize_path;
 
 use app::App;
-use cli::{OutputMode, parse_cli, print_help};
+use cli::{Command, EXIT_USAGE, OutputMode, parse_cli, print_help};
+use commands::run_command;
 use output::{print_json, print_oneline};
-use paths::normalize_path;
 use ui::draw_ui;
 
 const TICK_INTERVAL: Duration = Duration::from_secs(1);
 
-fn main() -> stdio::Result<()> {
-    let cli = parse_cli(std::env::args().skip(1));
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("skyset: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> stdio::Result<ExitCode> {
+    let cli = match parse_cli(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(errors) => {
+            for err in errors {
+                eprintln!("skyset: {err}");
+            }
+            return Ok(ExitCode::from(EXIT_USAGE));
+        }
+    };
     if cli.show_help {
         print_help();
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     let normalized_path = normalize_path(cli.config_path);
+    if !matches!(cli.command, Command::Tui) {
+        return Ok(run_command(cli.command, normalized_path, cli.dry_run));
+    }
+
     let mut app = App::new(normalized_path);
     app.apply_overrides(&cli.overrides);
 
     match cli.output_mode {
+        OutputMode::Oneline | OutputMode::Json if app.read_status().is_error() => {
+            eprintln!("skyset: {}", app.read_status().label());
+            return Ok(ExitCode::FAILURE);
+        }
         OutputMode::Oneline => {
-            print_oneline(&app);
-            return Ok(());
+            print_oneline(app.path(), app.skyset());
+            return Ok(ExitCode::SUCCESS);
         }
         OutputMode::Json => {
-            if let Err(err) = print_json(&app) {
-                return Err(stdio::Error::new(stdio::ErrorKind::Other, err));
+            if let Err(err) = print_json(app.skyset()) {
+                return Err(stdio::Error::other(err));
             }
-            return Ok(());
+            return Ok(ExitCode::SUCCESS);
         }
         OutputMode::Tui => {}
     }
@@ -63,7 +94,8 @@ fn main() -> stdio::Result<()> {
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
-    res
+    app.finish_hooks();
+    res.map(|_| ExitCode::SUCCESS)
 }
 
 fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> stdio::Result<()> {
@@ -74,16 +106,16 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
             .poll_interval()
             .checked_sub(app.last_poll_elapsed())
             .unwrap_or(Duration::from_secs(0));
-        let timeout = std::cmp::min(poll_deadline, TICK_INTERVAL);
+        let timeout = poll_deadline.min(TICK_INTERVAL).min(app.watch_timeout());
 
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if handle_key(app, key)? {
-                    return Ok(());
-                }
-            }
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+            && handle_key(app, key)?
+        {
+            return Ok(());
         }
 
+        app.check_for_changes();
         if app.last_poll_elapsed() >= app.poll_interval() {
             app.reload_from_disk();
         }
@@ -91,12 +123,26 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
 }
 
 fn handle_key(app: &mut App, key: KeyEvent) -> stdio::Result<bool> {
+    if app.conflict().is_some() {
+        return Ok(handle_conflict_key(app, key));
+    }
+    if app.history_pane().is_some() {
+        handle_history_key(app, key);
+        return Ok(false);
+    }
+    if app.picker().is_some() {
+        handle_picker_key(app, key);
+        return Ok(false);
+    }
+
     match key.code {
         KeyCode::Char('q') => return Ok(true),
         KeyCode::Tab => app.editor_next(),
         KeyCode::BackTab => app.editor_previous(),
         KeyCode::Enter => app.apply_editor_input(),
         KeyCode::Char('s') => app.save(),
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => app.redo(),
+        KeyCode::Char('u') if !app.is_editing() => app.undo(),
         KeyCode::Char('r') => {
             if app.is_editing() {
                 app.push_char('r');
@@ -105,6 +151,8 @@ fn handle_key(app: &mut App, key: KeyEvent) -> stdio::Result<bool> {
             }
         }
         KeyCode::Char('x') => app.reset(),
+        KeyCode::Char('h') if !app.is_editing() => app.open_history(),
+        KeyCode::Char('p') if app.editor().current_field().is_color() => app.open_picker(),
         KeyCode::Char(c) => {
             if key.modifiers.contains(KeyModifiers::CONTROL) {
                 return Ok(false);
@@ -120,21 +168,237 @@ fn handle_key(app: &mut App, key: KeyEvent) -> stdio::Result<bool> {
     Ok(false)
 }
 
+fn handle_conflict_key(app: &mut App, key: KeyEvent) -> bool {
+    let merging = app.conflict().is_some_and(|conflict| conflict.is_merging());
+    match (merging, key.code) {
+        (false, KeyCode::Char('q')) => return true,
+        (false, KeyCode::Char('m')) => app.keep_mine(),
+        (false, KeyCode::Char('t')) => app.take_theirs(),
+        (false, KeyCode::Char('f')) => app.set_merging(true),
+        (true, KeyCode::Up) => app.conflict_previous(),
+        (true, KeyCode::Down) => app.conflict_next(),
+        (true, KeyCode::Left | KeyCode::Right | KeyCode::Char(' ')) => app.conflict_toggle(),
+        (true, KeyCode::Enter) => app.apply_merge(),
+        (true, KeyCode::Esc) => app.set_merging(false),
+        _ => {}
+    }
+    false
+}
+
+fn handle_history_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => app.history_previous(),
+        KeyCode::Down | KeyCode::Char('j') => app.history_next(),
+        KeyCode::Enter => app.restore_from_history(),
+        KeyCode::Esc | KeyCode::Char('h' | 'q') => app.close_history(),
+        _ => {}
+    }
+}
+
+fn handle_picker_key(app: &mut App, key: KeyEvent) {
+    let step = if key.modifiers.contains(KeyModifiers::SHIFT) {
+        10
+    } else {
+        1
+    };
+    match key.code {
+        KeyCode::Up => app.picker_previous_channel(),
+        KeyCode::Down => app.picker_next_channel(),
+        KeyCode::Left => app.picker_adjust(-step),
+        KeyCode::Right => app.picker_adjust(step),
+        KeyCode::Enter => app.close_picker(true),
+        KeyCode::Esc => app.close_picker(false),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn normalize_hex_formats() {
-        assert_eq!(editor::normalize_hex("#abcdef").unwrap(), "#ABCDEF");
-        assert_eq!(editor::normalize_hex("123456").unwrap(), "#123456");
-        assert!(editor::normalize_hex("#12345").is_err());
+    fn picker_sliders_update_hex() {
+        let mut picker = picker::ColorPicker::open(skyset::field::FieldId::Accent, "#7C4DFF");
+        picker.adjust(3);
+        assert_eq!(picker.hex(), "#7F4DFF");
+        picker.next_channel();
+        picker.next_channel();
+        picker.next_channel();
+        picker.adjust(120);
+        let (h, _, _) = skyset::color::rgb_to_hsl(picker.rgb().0, picker.rgb().1, picker.rgb().2);
+        assert!((h - 18.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn picker_cancel_restores_invalid_and_empty_values() {
+        let dir = test_support::TempDir::new("picker");
+        let path = dir.join("latest.yml");
+        std::fs::write(
+            &path,
+            "palette:\n  secondary: teal\ngradients:\n  hero: []\n",
+        )
+        .unwrap();
+        let mut app = app::App::new(path);
+        let before = app.skyset().clone();
+
+        for field in [
+            skyset::field::FieldId::Secondary,
+            skyset::field::FieldId::Hero1,
+        ] {
+            while app.editor().current_field() != field {
+                app.editor_next();
+            }
+            app.open_picker();
+            app.picker_adjust(40);
+            assert_ne!(app.skyset(), &before);
+            app.close_picker(false);
+            assert_eq!(app.skyset(), &before);
+            assert!(!app.is_dirty());
+        }
+    }
+
+    #[test]
+    fn reload_closes_open_picker() {
+        let dir = test_support::TempDir::new("picker-reload");
+        let path = dir.join("latest.yml");
+        std::fs::write(&path, "theme:\n  accent: '#111111'\n").unwrap();
+        let mut app = app::App::new(path.clone());
+        while app.editor().current_field() != skyset::field::FieldId::Accent {
+            app.editor_next();
+        }
+        app.open_picker();
+        app.picker_adjust(40);
+
+        std::fs::write(&path, "theme:\n  accent: '#222222'\n").unwrap();
+        app.reload_from_disk();
+        assert!(app.picker().is_none());
+        assert!(app.conflict().is_none());
+        assert_eq!(app.skyset().theme.accent, "#222222");
+        assert!(!app.is_dirty());
+
+        app.close_picker(false);
+        assert_eq!(app.skyset().theme.accent, "#222222");
+    }
+
+    #[test]
+    fn undo_and_redo_restore_snapshots() {
+        let mut stack = undo::UndoStack::default();
+        let original = skyset::model::Skyset::default();
+        let mut edited = original.clone();
+        edited.message = "edited".to_string();
+
+        stack.record(original.clone());
+        let restored = stack.undo(&edited).unwrap();
+        assert_eq!(restored, original);
+        assert_eq!(stack.redo(&restored).unwrap(), edited);
+        assert!(stack.redo(&edited).is_none());
+    }
+
+    #[test]
+    fn conflict_merges_selected_fields() {
+        let mut mine = skyset::model::Skyset::default();
+        mine.theme.accent = "#111111".to_string();
+        mine.message = "mine".to_string();
+        let mut theirs = skyset::model::Skyset::default();
+        theirs.theme.accent = "#222222".to_string();
+        theirs.message = "theirs".to_string();
+
+        let mut conflict = conflict::Conflict::new(&mine, theirs, String::new(), None);
+        assert_eq!(conflict.fields().len(), 2);
+        conflict.toggle();
+        let merged = conflict.merged(&mine);
+        assert_eq!(merged.message, "theirs");
+        assert_eq!(merged.theme.accent, "#111111");
+    }
+
+    fn edit_message(app: &mut app::App, text: &str) {
+        while app.editor().current_field() != skyset::field::FieldId::Message {
+            app.editor_next();
+        }
+        for value in text.chars() {
+            app.push_char(value);
+        }
+        app.apply_editor_input();
+        assert!(app.is_dirty());
+    }
+
+    #[test]
+    fn reload_keeps_edits_when_no_listed_field_changed() {
+        let dir = test_support::TempDir::new("rebase");
+        let path = dir.join("latest.yml");
+        std::fs::write(&path, "message: hi\nupdated_at: one\n").unwrap();
+        let mut app = app::App::new(path.clone());
+        edit_message(&mut app, " there");
+
+        std::fs::write(&path, "message: hi\nupdated_at: two\n").unwrap();
+        app.reload_from_disk();
+        assert!(app.conflict().is_none());
+        assert_eq!(app.skyset().message, "hi there");
+        assert_eq!(app.skyset().updated_at, "two");
+
+        std::fs::remove_file(&path).unwrap();
+        app.reload_from_disk();
+        assert!(matches!(app.read_status(), skyset::io::ReadStatus::Missing));
+        assert_eq!(app.skyset().message, "hi there");
+        assert!(app.is_dirty());
+    }
+
+    #[test]
+    fn subcommands_keep_config_file_and_positionals() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        let cli = parse_cli(args(&[
+            "--config-file",
+            "a.yml",
+            "set",
+            "accent=#fff",
+            "message=hi",
+        ]))
+        .expect("set parses");
+        assert_eq!(cli.config_path, Some("a.yml".into()));
+        match cli.command {
+            Command::Set { assignments, .. } => {
+                assert_eq!(assignments[0].0, skyset::field::FieldId::Accent)
+            }
+            _ => panic!("expected set"),
+        }
+
+        assert!(parse_cli(args(&["diff", "only-one.yml"])).is_err());
+        assert!(parse_cli(args(&["set", "accent"])).is_err());
+        assert!(parse_cli(args(&["set", "colour=#fff"])).is_err());
+
+        let errors = parse_cli(args(&["--accent", "notacolor", "--mesage", "hi", "--mode"]))
+            .err()
+            .expect("strict parsing rejects bad input");
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|err| err.contains("--mesage")));
+        assert!(errors.iter().any(|err| err.contains("#RRGGBB")));
+        assert!(errors.iter().any(|err| err == "--mode needs a value"));
+        assert!(parse_cli(args(&["--lenient", "--accent", "notacolor", "--mesage"])).is_ok());
+
+        let cli = parse_cli(args(&["--write", "--mode", "light"])).expect("write parses");
+        match cli.command {
+            Command::Set { assignments, .. } => {
+                assert_eq!(
+                    assignments,
+                    [(skyset::field::FieldId::ThemeMode, "light".into())]
+                )
+            }
+            _ => panic!("expected --write to become set"),
+        }
     }
 
     #[test]
-    fn normalize_path_for_config_dir() {
-        let input = Some(paths::home_dir().join(".config"));
-        let result = paths::normalize_path(input);
-        assert!(result.ends_with("skyset/latest.yml"));
+    fn watch_template_fills_placeholders() {
+        let format: output::WatchFormat = "{theme.accent:hex}|{message}|{missing}".parse().unwrap();
+        let skyset = skyset::model::Skyset {
+            message: "rain".to_string(),
+            ..Default::default()
+        };
+        let line = format
+            .render(std::path::Path::new("latest.yml"), &skyset)
+            .unwrap();
+        assert_eq!(line, format!("{}|rain|", &skyset.theme.accent[1..]));
+        assert!("yaml".parse::<output::WatchFormat>().is_err());
     }
 }
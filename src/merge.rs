@@ -0,0 +1,100 @@
+//! Merging partial documents into a `Skyset`.
+//!
+//! A patch is any YAML or JSON mapping shaped like (part of) `latest.yml`.
+//! It follows JSON Merge Patch (RFC 7396): mappings merge recursively,
+//! every other value replaces what was there, and `null` deletes the key.
+//! Deleted schema fields fall back to their defaults; deleted unknown keys
+//! are dropped.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{error::SkysetError, model::Skyset};
+
+/// Parses `text` as YAML (and therefore also JSON). `source` only labels
+/// errors, e.g. `<stdin>`.
+pub fn parse_patch(source: &Path, text: &str) -> Result<Value, SkysetError> {
+    if text.trim().is_empty() {
+        return Ok(Value::Object(Default::default()));
+    }
+    let patch = serde_yaml::from_str::<Value>(text)
+        .map_err(|err| SkysetError::from_yaml(source.to_path_buf(), err))?;
+    if !patch.is_object() {
+        return Err(SkysetError::Parse {
+            path: source.to_path_buf(),
+            message: "expected a mapping of Skyset fields".to_string(),
+            line: None,
+            column: None,
+        });
+    }
+    Ok(patch)
+}
+
+/// Applies `patch` to a copy of `skyset`. A patch that gives a field the
+/// wrong type is a parse error in `source`.
+pub fn apply_patch(source: &Path, skyset: &Skyset, patch: &Value) -> Result<Skyset, SkysetError> {
+    let mut document =
+        serde_json::to_value(skyset).map_err(|err| SkysetError::Serialize(err.to_string()))?;
+    merge_patch(&mut document, patch);
+    serde_json::from_value(document).map_err(|err| SkysetError::Parse {
+        path: source.to_path_buf(),
+        message: err.to_string(),
+        line: None,
+        column: None,
+    })
+}
+
+/// RFC 7396 `MergePatch(target, patch)` on plain JSON values.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_patch_merges_and_deletes() {
+        let mut skyset = Skyset {
+            message: "rain".to_string(),
+            ..Default::default()
+        };
+        skyset.extra.insert("x_source".into(), "cron".into());
+
+        let source = std::path::Path::new("<stdin>");
+        let patch = parse_patch(
+            source,
+            r##"{"palette": {"primary": "#112233"}, "message": null, "x_source": null}"##,
+        )
+        .unwrap();
+        let merged = apply_patch(source, &skyset, &patch).unwrap();
+        assert_eq!(merged.palette.primary, "#112233");
+        assert_eq!(merged.palette.secondary, skyset.palette.secondary);
+        assert_eq!(merged.message, "");
+        assert!(merged.extra.is_empty());
+
+        assert!(parse_patch(source, "- not\n- a mapping\n").is_err());
+
+        let wrong_type = parse_patch(source, r#"{"theme": {"accent": 5}}"#).unwrap();
+        match apply_patch(source, &skyset, &wrong_type) {
+            Err(SkysetError::Parse { path, .. }) => assert_eq!(path, source),
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
+use serde_yaml::Mapping;
 
 pub const SCHEMA_VERSION: u32 = 1;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Skyset {
     #[serde(rename = "_version")]
@@ -15,6 +16,10 @@ pub struct Skyset {
     pub theme: Theme,
     pub palette: Palette,
     pub gradients: Gradients,
+    /// Keys this version of the schema does not know about, kept so they
+    /// survive a round trip through the editor.
+    #[serde(flatten)]
+    pub extra: Mapping,
 }
 
 impl Default for Skyset {
@@ -29,15 +34,18 @@ impl Default for Skyset {
             theme: Theme::default(),
             palette: Palette::default(),
             gradients: Gradients::default(),
+            extra: Mapping::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Theme {
     pub mode: ThemeMode,
     pub accent: String,
+    #[serde(flatten)]
+    pub extra: Mapping,
 }
 
 impl Default for Theme {
@@ -45,13 +53,15 @@ impl Default for Theme {
         Self {
             mode: ThemeMode::Dark,
             accent: "#7C4DFF".to_string(),
+            extra: Mapping::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ThemeMode {
+    #[default]
     Dark,
     Light,
     System,
@@ -59,12 +69,6 @@ pub enum ThemeMode {
     Unknown,
 }
 
-impl Default for ThemeMode {
-    fn default() -> Self {
-        ThemeMode::Dark
-    }
-}
-
 pub fn theme_mode_label(mode: ThemeMode) -> &'static str {
     match mode {
         ThemeMode::Dark => "dark",
@@ -74,12 +78,14 @@ pub fn theme_mode_label(mode: ThemeMode) -> &'static str {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Palette {
     pub primary: String,
     pub secondary: String,
     pub tertiary: String,
+    #[serde(flatten)]
+    pub extra: Mapping,
 }
 
 impl Default for Palette {
@@ -88,15 +94,18 @@ impl Default for Palette {
             primary: "#0E0E10".to_string(),
             secondary: "#1F1F23".to_string(),
             tertiary: "#2E2E35".to_string(),
+            extra: Mapping::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Gradients {
     pub background: Vec<String>,
     pub hero: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Mapping,
 }
 
 impl Default for Gradients {
@@ -108,6 +117,7 @@ impl Default for Gradients {
                 "#1D1D32".to_string(),
             ],
             hero: vec!["#7C4DFF".to_string(), "#5E35B1".to_string()],
+            extra: Mapping::new(),
         }
     }
 }
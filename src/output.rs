@@ -1,32 +1,117 @@
-use crate::{app::App, color::parse_hex_rgb};
-
-pub fn print_oneline(app: &App) {
-    let accent_block = color_block(&app.skyset().theme.accent);
-    let primary_block = color_block(&app.skyset().palette.primary);
-    let secondary_block = color_block(&app.skyset().palette.secondary);
-    let tertiary_block = color_block(&app.skyset().palette.tertiary);
-    let background_blocks = gradient_blocks(&app.skyset().gradients.background);
-    let hero_blocks = gradient_blocks(&app.skyset().gradients.hero);
+use std::{path::Path, str::FromStr};
+
+use skyset::{
+    color::parse_hex_rgb,
+    model::Skyset,
+    query::{self, Format},
+};
+
+/// What `skyset watch` prints for each version of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchFormat {
+    Oneline,
+    /// One compact JSON document per line.
+    Jsonl,
+    /// Text with `{path}` or `{path:format}` placeholders, e.g.
+    /// `{theme.accent:hex} {message}`.
+    Template(String),
+}
+
+impl FromStr for WatchFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "oneline" => Ok(WatchFormat::Oneline),
+            "jsonl" => Ok(WatchFormat::Jsonl),
+            template if template.contains('{') => Ok(WatchFormat::Template(template.to_string())),
+            _ => Err(format!(
+                "unknown watch format {value:?} (expected oneline, jsonl or a {{path}} template)"
+            )),
+        }
+    }
+}
+
+impl WatchFormat {
+    pub fn render(&self, path: &Path, skyset: &Skyset) -> Result<String, String> {
+        match self {
+            WatchFormat::Oneline => Ok(oneline(path, skyset)),
+            WatchFormat::Jsonl => serde_json::to_string(skyset).map_err(|err| err.to_string()),
+            WatchFormat::Template(template) => Ok(fill_template(template, skyset)),
+        }
+    }
+}
+
+pub fn oneline(path: &Path, skyset: &Skyset) -> String {
+    let accent_block = color_block(&skyset.theme.accent);
+    let primary_block = color_block(&skyset.palette.primary);
+    let secondary_block = color_block(&skyset.palette.secondary);
+    let tertiary_block = color_block(&skyset.palette.tertiary);
+    let background_blocks = gradient_blocks(&skyset.gradients.background);
+    let hero_blocks = gradient_blocks(&skyset.gradients.hero);
     let palette_blocks = format!("{}{}{}", primary_block, secondary_block, tertiary_block);
-    println!(
+    format!(
         "{} {} | msg=\"{}\" | palette: {} | background: {} | hero: {}",
-        app.path().display(),
+        path.display(),
         accent_block,
-        app.skyset().message,
+        skyset.message,
         palette_blocks,
         background_blocks,
         hero_blocks
-    );
+    )
 }
 
-pub fn print_json(app: &App) -> Result<(), String> {
-    serde_json::to_string_pretty(app.skyset())
+pub fn print_oneline(path: &Path, skyset: &Skyset) {
+    println!("{}", oneline(path, skyset));
+}
+
+pub fn print_json(skyset: &Skyset) -> Result<(), String> {
+    serde_json::to_string_pretty(skyset)
         .map(|payload| {
             println!("{}", payload);
         })
         .map_err(|err| err.to_string())
 }
 
+/// Accent, palette and gradient stops as a row of colored blocks.
+pub fn swatches(skyset: &Skyset) -> String {
+    format!(
+        "{}|{}{}{}|{}|{}",
+        color_block(&skyset.theme.accent),
+        color_block(&skyset.palette.primary),
+        color_block(&skyset.palette.secondary),
+        color_block(&skyset.palette.tertiary),
+        gradient_blocks(&skyset.gradients.background),
+        gradient_blocks(&skyset.gradients.hero)
+    )
+}
+
+/// Replaces each `{path}` / `{path:format}` with the queried value. Missing
+/// values and bad formats become empty strings so one bad placeholder does
+/// not stop the stream.
+fn fill_template(template: &str, skyset: &Skyset) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..start + len];
+        let (key, format) = match placeholder.split_once(':') {
+            Some((key, format)) => (key, format.parse::<Format>().ok()),
+            None => (placeholder, Some(Format::Plain)),
+        };
+        let value = format.zip(query::lookup(skyset, key));
+        if let Some(text) = value.and_then(|(format, value)| query::render(&value, format).ok()) {
+            rendered.push_str(&text);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
 fn color_block(value: &str) -> String {
     if let Some((r, g, b)) = parse_hex_rgb(value) {
         format!("\x1b[48;2;{r};{g};{b}m  \x1b[0m")
@@ -39,3 +39,15 @@ pub fn home_dir() -> PathBuf {
         .map(|dirs| dirs.home_dir().to_path_buf())
         .unwrap_or_else(|| PathBuf::from("/"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path_for_config_dir() {
+        let input = Some(home_dir().join(".config"));
+        let result = normalize_path(input);
+        assert!(result.ends_with("skyset/latest.yml"));
+    }
+}
@@ -0,0 +1,138 @@
+use skyset::{
+    color::{format_hex, hsl_to_rgb, parse_hex_rgb, rgb_to_hsl},
+    field::FieldId,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    Hue,
+    Saturation,
+    Lightness,
+}
+
+const CHANNELS: [Channel; 6] = [
+    Channel::Red,
+    Channel::Green,
+    Channel::Blue,
+    Channel::Hue,
+    Channel::Saturation,
+    Channel::Lightness,
+];
+
+impl Channel {
+    pub fn label(self) -> &'static str {
+        match self {
+            Channel::Red => "R",
+            Channel::Green => "G",
+            Channel::Blue => "B",
+            Channel::Hue => "H",
+            Channel::Saturation => "S",
+            Channel::Lightness => "L",
+        }
+    }
+
+    pub fn max(self) -> u16 {
+        match self {
+            Channel::Red | Channel::Green | Channel::Blue => 255,
+            Channel::Hue => 359,
+            Channel::Saturation | Channel::Lightness => 100,
+        }
+    }
+}
+
+/// Slider state for editing one color field.
+///
+/// HSL is tracked separately from RGB so that dragging saturation or
+/// lightness to an extreme and back does not lose the hue.
+pub struct ColorPicker {
+    field: FieldId,
+    original: String,
+    rgb: (u8, u8, u8),
+    hsl: (f64, f64, f64),
+    selected: usize,
+}
+
+impl ColorPicker {
+    pub fn open(field: FieldId, value: &str) -> Self {
+        let rgb = parse_hex_rgb(value).unwrap_or((0, 0, 0));
+        Self {
+            field,
+            original: value.to_string(),
+            rgb,
+            hsl: rgb_to_hsl(rgb.0, rgb.1, rgb.2),
+            selected: 0,
+        }
+    }
+
+    pub fn field(&self) -> FieldId {
+        self.field
+    }
+
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    pub fn channels(&self) -> &[Channel] {
+        &CHANNELS
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn hex(&self) -> String {
+        format_hex(self.rgb.0, self.rgb.1, self.rgb.2)
+    }
+
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        self.rgb
+    }
+
+    pub fn next_channel(&mut self) {
+        self.selected = (self.selected + 1) % CHANNELS.len();
+    }
+
+    pub fn previous_channel(&mut self) {
+        self.selected = (self.selected + CHANNELS.len() - 1) % CHANNELS.len();
+    }
+
+    pub fn value(&self, channel: Channel) -> u16 {
+        match channel {
+            Channel::Red => self.rgb.0.into(),
+            Channel::Green => self.rgb.1.into(),
+            Channel::Blue => self.rgb.2.into(),
+            Channel::Hue => self.hsl.0.round() as u16 % 360,
+            Channel::Saturation => (self.hsl.1 * 100.0).round() as u16,
+            Channel::Lightness => (self.hsl.2 * 100.0).round() as u16,
+        }
+    }
+
+    pub fn adjust(&mut self, delta: i32) {
+        let channel = CHANNELS[self.selected];
+        let step = |value: u8| (i32::from(value) + delta).clamp(0, 255) as u8;
+        match channel {
+            Channel::Red => self.rgb.0 = step(self.rgb.0),
+            Channel::Green => self.rgb.1 = step(self.rgb.1),
+            Channel::Blue => self.rgb.2 = step(self.rgb.2),
+            Channel::Hue => self.hsl.0 = (self.hsl.0 + f64::from(delta)).rem_euclid(360.0),
+            Channel::Saturation => {
+                self.hsl.1 = (self.hsl.1 + f64::from(delta) / 100.0).clamp(0.0, 1.0)
+            }
+            Channel::Lightness => {
+                self.hsl.2 = (self.hsl.2 + f64::from(delta) / 100.0).clamp(0.0, 1.0)
+            }
+        }
+
+        match channel {
+            Channel::Red | Channel::Green | Channel::Blue => {
+                self.hsl = rgb_to_hsl(self.rgb.0, self.rgb.1, self.rgb.2);
+            }
+            Channel::Hue | Channel::Saturation | Channel::Lightness => {
+                self.rgb = hsl_to_rgb(self.hsl.0, self.hsl.1, self.hsl.2);
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Pulling single values out of a `Skyset` by path, for shell consumers.
+//!
+//! Paths use the same syntax as [`diff`](crate::diff): dotted keys with
+//! `[index]` for sequence items, e.g. `theme.accent`,
+//! `gradients.background[1]`, or a whole subtree like `palette`.
+
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::{
+    color::{format_hex, parse_hex_rgb},
+    model::Skyset,
+};
+
+/// How [`render`] prints a value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Strings as-is, everything else as compact JSON.
+    #[default]
+    Plain,
+    /// `RRGGBB`, without the `#`.
+    Hex,
+    /// `r,g,b` in decimal.
+    Rgb,
+    /// `0xRRGGBB`.
+    ZeroX,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "plain" => Ok(Format::Plain),
+            "hex" => Ok(Format::Hex),
+            "rgb" => Ok(Format::Rgb),
+            "0x" => Ok(Format::ZeroX),
+            _ => Err(format!(
+                "unknown format {value:?} (expected plain, hex, rgb or 0x)"
+            )),
+        }
+    }
+}
+
+/// The value at `path`, or `None` if there is nothing there.
+pub fn lookup(skyset: &Skyset, path: &str) -> Option<Value> {
+    let mut current = serde_json::to_value(skyset).ok()?;
+    for segment in path.split('.') {
+        let (key, indexes) = match segment.find('[') {
+            Some(start) => segment.split_at(start),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get_mut(key)?.take();
+        }
+        let mut rest = indexes;
+        while let Some(inner) = rest.strip_prefix('[') {
+            let (index, tail) = inner.split_once(']')?;
+            current = current.get_mut(index.parse::<usize>().ok()?)?.take();
+            rest = tail;
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(current)
+}
+
+/// Formats `value` for printing. Color formats also accept a sequence of
+/// colors (a gradient), printed space-separated.
+pub fn render(value: &Value, format: Format) -> Result<String, String> {
+    match (format, value) {
+        (Format::Plain, Value::String(text)) => Ok(text.clone()),
+        (Format::Plain, other) => Ok(other.to_string()),
+        (_, Value::Array(items)) => items
+            .iter()
+            .map(|item| render(item, format))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|items| items.join(" ")),
+        (_, Value::String(text)) => {
+            let (r, g, b) =
+                parse_hex_rgb(text).ok_or_else(|| format!("{text:?} is not a color"))?;
+            Ok(match format {
+                Format::Hex => format_hex(r, g, b)[1..].to_string(),
+                Format::Rgb => format!("{r},{g},{b}"),
+                _ => format!("0x{}", &format_hex(r, g, b)[1..]),
+            })
+        }
+        (_, other) => Err(format!("{other} is not a color")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_paths_and_formats() {
+        let skyset = Skyset::default();
+        let stop = lookup(&skyset, "gradients.background[1]").unwrap();
+        assert_eq!(stop, serde_json::json!(skyset.gradients.background[1]));
+        assert!(lookup(&skyset, "palette").unwrap().is_object());
+        assert!(lookup(&skyset, "gradients.background[9]").is_none());
+        assert!(lookup(&skyset, "theme.nope").is_none());
+
+        let accent = serde_json::json!("#0a0B0c");
+        assert_eq!(render(&accent, Format::Hex).unwrap(), "0A0B0C");
+        assert_eq!(render(&accent, Format::Rgb).unwrap(), "10,11,12");
+        assert_eq!(render(&accent, Format::ZeroX).unwrap(), "0x0A0B0C");
+        assert!(render(&serde_json::json!(true), Format::Hex).is_err());
+    }
+}
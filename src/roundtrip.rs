@@ -0,0 +1,429 @@
+//! Writes a `Skyset` back over the text it was read from.
+//!
+//! Only the values that changed are rewritten in place, so comments, key
+//! order and quoting in hand-maintained files survive a save. Keys that are
+//! new are appended to the block they belong to. Anything the line scanner
+//! does not understand (block scalars, multi-line flow values, ...) falls
+//! back to a plain re-serialization, which still keeps unknown keys.
+
+use std::collections::HashMap;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::{error::SkysetError, model::Skyset};
+
+pub fn render(raw: &str, skyset: &Skyset) -> Result<String, SkysetError> {
+    let fresh =
+        serde_yaml::to_string(skyset).map_err(|err| SkysetError::Serialize(err.to_string()))?;
+    if raw.trim().is_empty() {
+        return Ok(fresh);
+    }
+
+    let patched = patch(raw, skyset);
+    // Never trust the patch blindly: it must read back as exactly what we meant to write.
+    match patched {
+        Some(text) if serde_yaml::from_str::<Skyset>(&text).ok().as_ref() == Some(skyset) => {
+            Ok(text)
+        }
+        _ => Ok(fresh),
+    }
+}
+
+/// A scalar or flow value written on the same line as its key (or dash).
+struct Leaf {
+    path: Vec<String>,
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+/// A block mapping, including the implicit top-level one.
+struct Block {
+    path: Vec<String>,
+    indent: usize,
+    child_indent: Option<usize>,
+    end: usize,
+}
+
+/// A block sequence (`- item` lines) under a key.
+struct Sequence {
+    path: Vec<String>,
+    dash_indent: usize,
+    items: Vec<Leaf>,
+}
+
+struct Scan {
+    leaves: Vec<Leaf>,
+    blocks: Vec<Block>,
+    sequences: Vec<Sequence>,
+}
+
+enum Edit {
+    Inline {
+        start: usize,
+        end: usize,
+        text: String,
+    },
+    Replace {
+        last: usize,
+        lines: Vec<String>,
+    },
+}
+
+fn patch(raw: &str, skyset: &Skyset) -> Option<String> {
+    let lines: Vec<&str> = raw.lines().collect();
+    let scan = scan(&lines)?;
+    let old: Value = serde_yaml::from_str(raw).ok()?;
+    let new = serde_yaml::to_value(skyset).ok()?;
+
+    let mut edits: HashMap<usize, Edit> = HashMap::new();
+    for leaf in &scan.leaves {
+        let value = lookup(&new, &leaf.path)?;
+        if lookup(&old, &leaf.path) == Some(value) {
+            continue;
+        }
+        let original = &lines[leaf.line][leaf.start..leaf.end];
+        let text = render_inline(value, original)?;
+        edits.insert(
+            leaf.line,
+            Edit::Inline {
+                start: leaf.start,
+                end: leaf.end,
+                text,
+            },
+        );
+    }
+
+    for sequence in &scan.sequences {
+        let value = lookup(&new, &sequence.path)?;
+        if lookup(&old, &sequence.path) == Some(value) {
+            continue;
+        }
+        let items = value.as_sequence()?;
+        let first = sequence.items.first()?;
+        if items.len() == sequence.items.len() {
+            for (item, leaf) in items.iter().zip(&sequence.items) {
+                let original = &lines[leaf.line][leaf.start..leaf.end];
+                let text = render_inline(item, original)?;
+                edits.insert(
+                    leaf.line,
+                    Edit::Inline {
+                        start: leaf.start,
+                        end: leaf.end,
+                        text,
+                    },
+                );
+            }
+        } else {
+            let style = &lines[first.line][first.start..first.end];
+            let replacement = items
+                .iter()
+                .map(|item| {
+                    render_inline(item, style)
+                        .map(|text| format!("{}- {text}", " ".repeat(sequence.dash_indent)))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            edits.insert(
+                first.line,
+                Edit::Replace {
+                    last: sequence.items.last()?.line,
+                    lines: replacement,
+                },
+            );
+        }
+    }
+
+    let mut insertions: Vec<(usize, usize, String)> = Vec::new();
+    collect_insertions(&scan, new.as_mapping()?, &mut Vec::new(), &mut insertions)?;
+    insertions.sort_by_key(|(line, depth, _)| (*line, std::cmp::Reverse(*depth)));
+
+    let mut out = String::with_capacity(raw.len() + 64);
+    let mut idx = 0;
+    while idx < lines.len() {
+        let mut next = idx + 1;
+        match edits.remove(&idx) {
+            Some(Edit::Inline { start, end, text }) => {
+                out.push_str(&lines[idx][..start]);
+                out.push_str(&text);
+                out.push_str(&lines[idx][end..]);
+                out.push('\n');
+            }
+            Some(Edit::Replace {
+                last,
+                lines: new_lines,
+            }) => {
+                for line in new_lines {
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+                next = last + 1;
+            }
+            None => {
+                out.push_str(lines[idx]);
+                out.push('\n');
+            }
+        }
+        for line in idx..next {
+            for (_, _, text) in insertions.iter().filter(|(at, _, _)| *at == line) {
+                out.push_str(text);
+            }
+        }
+        idx = next;
+    }
+    Some(out)
+}
+
+fn collect_insertions(
+    scan: &Scan,
+    mapping: &Mapping,
+    path: &mut Vec<String>,
+    insertions: &mut Vec<(usize, usize, String)>,
+) -> Option<()> {
+    let block = scan.blocks.iter().find(|block| block.path == *path)?;
+    let indent = block
+        .child_indent
+        .unwrap_or(if path.is_empty() { 0 } else { block.indent + 2 });
+
+    for (key, value) in mapping {
+        let key = key.as_str()?;
+        path.push(key.to_string());
+        let has_block = scan.blocks.iter().any(|block| block.path == *path);
+        let present = has_block
+            || scan.leaves.iter().any(|leaf| leaf.path == *path)
+            || scan.sequences.iter().any(|seq| seq.path == *path);
+
+        if has_block && let Some(child) = value.as_mapping() {
+            collect_insertions(scan, child, path, insertions)?;
+        } else if !present {
+            let mut entry = Mapping::new();
+            entry.insert(Value::String(key.to_string()), value.clone());
+            let rendered = serde_yaml::to_string(&entry).ok()?;
+            let text = rendered
+                .lines()
+                .map(|line| format!("{}{line}\n", " ".repeat(indent)))
+                .collect::<String>();
+            insertions.push((block.end, path.len(), text));
+        }
+        path.pop();
+    }
+    Some(())
+}
+
+fn scan(lines: &[&str]) -> Option<Scan> {
+    let mut scan = Scan {
+        leaves: Vec::new(),
+        blocks: vec![Block {
+            path: Vec::new(),
+            indent: 0,
+            child_indent: None,
+            end: 0,
+        }],
+        sequences: Vec::new(),
+    };
+    // Open mappings as (indent, index into `scan.blocks`); the root is never popped.
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut current_seq: Option<usize> = None;
+    let mut empty_key: Option<(usize, usize)> = None;
+    let mut seen_content = false;
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.starts_with("---") && !seen_content {
+            continue;
+        }
+        if trimmed.starts_with("---") || trimmed.starts_with("...") || line.contains('\t') {
+            return None;
+        }
+        seen_content = true;
+        let indent = line.len() - trimmed.len();
+
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            let seq_idx = match (current_seq, empty_key) {
+                (Some(idx), _) if scan.sequences[idx].dash_indent == indent => idx,
+                (_, Some((key_indent, block_idx))) if indent >= key_indent => {
+                    let path = scan.blocks[block_idx].path.clone();
+                    scan.sequences.push(Sequence {
+                        path,
+                        dash_indent: indent,
+                        items: Vec::new(),
+                    });
+                    scan.sequences.len() - 1
+                }
+                _ => return None,
+            };
+            current_seq = Some(seq_idx);
+            empty_key = None;
+
+            let (start, end) = value_span(line, indent + 1)?;
+            if start == end {
+                return None;
+            }
+            let path = scan.sequences[seq_idx].path.clone();
+            scan.sequences[seq_idx].items.push(Leaf {
+                path,
+                line: line_idx,
+                start,
+                end,
+            });
+            touch(&mut scan, &stack, line_idx);
+            continue;
+        }
+
+        current_seq = None;
+        empty_key = None;
+        while stack.last().is_some_and(|(open, _)| *open >= indent) {
+            stack.pop();
+        }
+        let parent = stack.last().map(|(_, idx)| *idx).unwrap_or(0);
+        if scan.blocks[parent].child_indent.is_none() {
+            scan.blocks[parent].child_indent = Some(indent);
+        }
+        touch(&mut scan, &stack, line_idx);
+
+        let (key, colon) = split_key(trimmed)?;
+        let mut path = scan.blocks[parent].path.clone();
+        path.push(key);
+
+        let (start, end) = value_span(line, indent + colon + 1)?;
+        if start == end {
+            scan.blocks.push(Block {
+                path,
+                indent,
+                child_indent: None,
+                end: line_idx,
+            });
+            let block_idx = scan.blocks.len() - 1;
+            stack.push((indent, block_idx));
+            empty_key = Some((indent, block_idx));
+            continue;
+        }
+
+        let value = &line[start..end];
+        if value.starts_with('|') || value.starts_with('>') {
+            return None;
+        }
+        if (value.starts_with('[') && !value.ends_with(']'))
+            || (value.starts_with('{') && !value.ends_with('}'))
+        {
+            return None;
+        }
+        scan.leaves.push(Leaf {
+            path,
+            line: line_idx,
+            start,
+            end,
+        });
+    }
+
+    Some(scan)
+}
+
+/// Extends every open block (and the root) to cover `line`.
+fn touch(scan: &mut Scan, stack: &[(usize, usize)], line: usize) {
+    scan.blocks[0].end = line;
+    for (_, idx) in stack {
+        scan.blocks[*idx].end = line;
+    }
+}
+
+/// Splits `key: rest` and returns the key and the byte offset of the colon.
+fn split_key(text: &str) -> Option<(String, usize)> {
+    if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let close = text[1..].find(quote)? + 1;
+        let after = &text[close + 1..];
+        if !after.starts_with(':') {
+            return None;
+        }
+        return Some((text[1..close].to_string(), close + 1));
+    }
+
+    let bytes = text.as_bytes();
+    let colon = (0..bytes.len())
+        .find(|idx| bytes[*idx] == b':' && (idx + 1 == bytes.len() || bytes[idx + 1] == b' '))?;
+    let key = text[..colon].trim();
+    if key.is_empty() || key.starts_with(['{', '[', '?', '&', '*', '!', '#']) {
+        return None;
+    }
+    Some((key.to_string(), colon))
+}
+
+/// Byte range of the value that starts at or after `from`, excluding any
+/// surrounding whitespace and trailing comment.
+fn value_span(line: &str, from: usize) -> Option<(usize, usize)> {
+    let rest = line.get(from..)?;
+    let start = from + (rest.len() - rest.trim_start().len());
+    let mut end = start;
+    let mut quote: Option<char> = None;
+    let mut prev = ' ';
+    for (offset, ch) in line[start..].char_indices() {
+        match quote {
+            Some('"') if ch == '"' && prev != '\\' => quote = None,
+            Some('\'') if ch == '\'' => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '#' && prev.is_whitespace() => break,
+            None => {}
+        }
+        if !ch.is_whitespace() {
+            end = start + offset + ch.len_utf8();
+        }
+        prev = ch;
+    }
+    Some((start, end))
+}
+
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |current, key| {
+        current.as_mapping()?.get(key.as_str())
+    })
+}
+
+/// Renders `value` for the spot where `original` was, keeping its quote style.
+fn render_inline(value: &Value, original: &str) -> Option<String> {
+    match value {
+        Value::String(text) if original.starts_with('"') => serde_json::to_string(text).ok(),
+        Value::String(text) if original.starts_with('\'') => {
+            Some(format!("'{}'", text.replace('\'', "''")))
+        }
+        Value::Sequence(items) => {
+            let inner = original.trim_start_matches('[').trim_start();
+            let rendered = items
+                .iter()
+                .map(|item| render_inline(item, inner))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("[{}]", rendered.join(", ")))
+        }
+        Value::Mapping(_) | Value::Tagged(_) => None,
+        _ => {
+            let text = serde_yaml::to_string(value).ok()?;
+            let text = text.trim_end();
+            (!text.contains('\n')).then(|| text.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_keeps_comments_and_unknown_keys() {
+        let raw = "# produced by hand\n_version: 1\norigin: manual # who\nx_top: {keep: true}\ntheme:\n  accent: \"#112233\" # brand\n  x_theme: 5\ngradients:\n  hero:\n    - '#000000'\n    - '#111111'\n";
+        let mut skyset: Skyset = serde_yaml::from_str(raw).unwrap();
+        skyset.theme.accent = "#ABCDEF".to_string();
+        skyset.gradients.hero[1] = "#222222".to_string();
+
+        let rendered = render(raw, &skyset).unwrap();
+        assert!(rendered.starts_with("# produced by hand\n_version: 1\norigin: manual # who\n"));
+        assert!(rendered.contains("  accent: \"#ABCDEF\" # brand\n"));
+        assert!(rendered.contains("    - '#222222'\n"));
+        assert!(rendered.contains("x_top: {keep: true}\n"));
+        assert!(rendered.contains("  x_theme: 5\n"));
+        assert!(rendered.contains("palette:\n  primary:"));
+        let reparsed: Skyset = serde_yaml::from_str(&rendered).unwrap();
+        assert_eq!(reparsed, skyset);
+    }
+}
@@ -0,0 +1,357 @@
+//! A small local HTTP server for browser-based consumers.
+//!
+//! `skyset serve` answers:
+//!
+//! - `GET /theme.json`: the current `Skyset` as JSON
+//! - `GET /theme.css`: the theme as CSS custom properties on `:root`, as
+//!   `skyset export css` writes it
+//! - `GET /events`: a Server-Sent Events stream with one `theme` event for
+//!   the current values and another for every change
+//!
+//! It binds to `127.0.0.1` unless told otherwise and shares the file
+//! watcher with everything else, so it is always in step with `latest.yml`.
+//! Requests must name the server itself in `Host` (`localhost`, a loopback
+//! address or the bound address, with the port), which keeps DNS rebinding
+//! pages out, and no other origin may read the responses unless
+//! [`Server::with_cors`] allows it.
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    error::SkysetError,
+    export::{Css, Exporter},
+    model::Skyset,
+    watch::Watcher,
+};
+
+pub const DEFAULT_PORT: u16 = 7477;
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest request line plus headers; requests never need a body.
+const MAX_HEAD: u64 = 8 * 1024;
+/// Open connections, event streams included; more are refused with 503.
+const MAX_CONNECTIONS: usize = 32;
+/// How long a refused connection may take to send the rest of its request.
+const REFUSED_LINGER: Duration = Duration::from_millis(100);
+/// Events a stream may fall behind by before it is closed.
+const EVENT_BACKLOG: usize = 16;
+
+struct Shared {
+    current: Skyset,
+    subscribers: Vec<SyncSender<String>>,
+}
+
+/// What every connection checks its request and response against.
+struct Policy {
+    /// Accepted `Host` values, lowercase.
+    hosts: Vec<String>,
+    cors: Option<String>,
+}
+
+pub struct Server {
+    config_path: PathBuf,
+    listener: TcpListener,
+    cors: Option<String>,
+}
+
+impl Server {
+    pub fn bind(config_path: &Path, addr: impl ToSocketAddrs) -> Result<Self, SkysetError> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|err| SkysetError::from_io(PathBuf::from("<http listener>"), err))?;
+        Ok(Self {
+            config_path: config_path.to_path_buf(),
+            listener,
+            cors: None,
+        })
+    }
+
+    /// Lets pages from `origin` (e.g. `http://localhost:3000`) read the
+    /// responses.
+    pub fn with_cors(mut self, origin: impl Into<String>) -> Self {
+        self.cors = Some(origin.into());
+        self
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr().ok()
+    }
+
+    /// Serves requests until the process exits.
+    pub fn run(self) -> Result<(), SkysetError> {
+        let policy = Arc::new(Policy {
+            hosts: self.local_addr().map(allowed_hosts).unwrap_or_default(),
+            cors: self.cors,
+        });
+        let mut watcher = Watcher::new(&self.config_path);
+        let shared = Arc::new(Mutex::new(Shared {
+            current: watcher.current().clone(),
+            subscribers: Vec::new(),
+        }));
+
+        let listener = self.listener;
+        let accepting = Arc::clone(&shared);
+        thread::spawn(move || {
+            let open = Arc::new(AtomicUsize::new(0));
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                let Some(slot) = Slot::claim(&open) else {
+                    let _ = respond(
+                        &mut stream,
+                        &policy,
+                        "503 Service Unavailable",
+                        "text/plain",
+                        "too many connections\n",
+                    );
+                    // Briefly, so a client that does not close cannot stall
+                    // the listener.
+                    let _ = stream.set_read_timeout(Some(REFUSED_LINGER));
+                    linger(&stream);
+                    continue;
+                };
+                let shared = Arc::clone(&accepting);
+                let policy = Arc::clone(&policy);
+                thread::spawn(move || {
+                    handle(stream, &shared, &policy);
+                    drop(slot);
+                });
+            }
+        });
+
+        loop {
+            let skyset = match watcher.next_change() {
+                Ok(change) => change.new,
+                Err(_) => continue,
+            };
+            let Ok(mut shared) = shared.lock() else {
+                return Ok(());
+            };
+            let event = theme_event(&skyset);
+            shared.current = skyset;
+            // Streams write on their own threads; one that is gone or too
+            // far behind is dropped here, which ends it.
+            shared
+                .subscribers
+                .retain(|subscriber| subscriber.try_send(event.clone()).is_ok());
+        }
+    }
+}
+
+/// One of the [`MAX_CONNECTIONS`], given back when dropped.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn claim(open: &Arc<AtomicUsize>) -> Option<Self> {
+        open.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+            (count < MAX_CONNECTIONS).then_some(count + 1)
+        })
+        .ok()
+        .map(|_| Self(Arc::clone(open)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// `localhost` and the loopback addresses, plus the bound address if it is
+/// a specific one, each with the port (and without it on port 80).
+fn allowed_hosts(addr: SocketAddr) -> Vec<String> {
+    let mut names = vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "[::1]".to_string(),
+    ];
+    let bound = match addr.ip() {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{ip}]"),
+    };
+    if !addr.ip().is_unspecified() && !names.contains(&bound) {
+        names.push(bound);
+    }
+    let port = addr.port();
+    let mut hosts: Vec<String> = names.iter().map(|name| format!("{name}:{port}")).collect();
+    if port == 80 {
+        hosts.extend(names);
+    }
+    hosts
+}
+
+struct Request {
+    method: String,
+    target: String,
+    host: Option<String>,
+}
+
+fn handle(mut stream: TcpStream, shared: &Mutex<Shared>, policy: &Policy) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(status) => {
+            let _ = respond(&mut stream, policy, status, "text/plain", "bad request\n");
+            linger(&stream);
+            return;
+        }
+    };
+    let host_allowed = request
+        .host
+        .is_some_and(|host| policy.hosts.contains(&host.to_ascii_lowercase()));
+    if !host_allowed {
+        let _ = respond(
+            &mut stream,
+            policy,
+            "403 Forbidden",
+            "text/plain",
+            "unexpected Host header\n",
+        );
+        return;
+    }
+    if request.method != "GET" {
+        let _ = respond(
+            &mut stream,
+            policy,
+            "405 Method Not Allowed",
+            "text/plain",
+            "GET only\n",
+        );
+        return;
+    }
+    let Ok(mut shared) = shared.lock() else {
+        return;
+    };
+
+    let (content_type, body) = match request.target.split('?').next().unwrap_or_default() {
+        "/theme.json" => (
+            "application/json",
+            serde_json::to_string_pretty(&shared.current).unwrap_or_default(),
+        ),
+        "/theme.css" => ("text/css", Css.render(&shared.current).unwrap_or_default()),
+        "/events" => {
+            // Subscribed while still holding the lock, so no update can
+            // slip in between the first event and the subscription.
+            let (subscriber, events) = mpsc::sync_channel(EVENT_BACKLOG);
+            let _ = subscriber.try_send(theme_event(&shared.current));
+            shared.subscribers.push(subscriber);
+            drop(shared);
+            stream_events(stream, policy, events);
+            return;
+        }
+        _ => {
+            drop(shared);
+            let _ = respond(
+                &mut stream,
+                policy,
+                "404 Not Found",
+                "text/plain",
+                "not found\n",
+            );
+            return;
+        }
+    };
+    drop(shared);
+    let _ = respond(&mut stream, policy, "200 OK", content_type, &body);
+}
+
+/// Closes after answering early. Closing with input still unread would
+/// reset the connection before the client saw the answer, so the rest of
+/// the request is read and discarded first.
+fn linger(stream: &TcpStream) {
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = io::copy(&mut stream.take(MAX_HEAD * 8), &mut io::sink());
+}
+
+/// Writes each event as it arrives until the client goes away or falls
+/// behind.
+fn stream_events(mut stream: TcpStream, policy: &Policy, events: Receiver<String>) {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n{}\r\n",
+        cors_header(policy)
+    );
+    if stream.write_all(head.as_bytes()).is_err() {
+        return;
+    }
+    for event in events {
+        if stream.write_all(event.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads the request line and the `Host` header, from at most
+/// [`MAX_HEAD`] bytes. The error is the status to answer with.
+fn read_request(stream: &TcpStream) -> Result<Request, &'static str> {
+    const BAD_REQUEST: &str = "400 Bad Request";
+    let mut reader = BufReader::new(stream.take(MAX_HEAD));
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(_) if !line.ends_with('\n') => {
+                return Err(if reader.get_ref().limit() == 0 {
+                    "431 Request Header Fields Too Large"
+                } else {
+                    BAD_REQUEST
+                });
+            }
+            Ok(_) if line.trim().is_empty() => break,
+            Ok(_) => lines.push(line),
+            Err(_) => return Err(BAD_REQUEST),
+        }
+    }
+
+    let mut lines = lines.into_iter();
+    let request_line = lines.next().ok_or(BAD_REQUEST)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or(BAD_REQUEST)?.to_string();
+    let target = parts.next().ok_or(BAD_REQUEST)?.to_string();
+    let host = lines.find_map(|header| {
+        let (name, value) = header.split_once(':')?;
+        name.eq_ignore_ascii_case("host")
+            .then(|| value.trim().to_string())
+    });
+    Ok(Request {
+        method,
+        target,
+        host,
+    })
+}
+
+fn cors_header(policy: &Policy) -> String {
+    policy
+        .cors
+        .as_ref()
+        .map(|origin| format!("Access-Control-Allow-Origin: {origin}\r\n"))
+        .unwrap_or_default()
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    policy: &Policy,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}; charset=utf-8\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{body}",
+        body.len(),
+        cors_header(policy)
+    )
+}
+
+fn theme_event(skyset: &Skyset) -> String {
+    let data = serde_json::to_string(skyset).unwrap_or_default();
+    format!("event: theme\ndata: {data}\n\n")
+}
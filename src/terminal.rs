@@ -0,0 +1,156 @@
+//! Recoloring terminals that are already open.
+//!
+//! `skyset apply-terminal` renders the theme as OSC escape sequences (see
+//! [`export::Sequences`](crate::export::Sequences)) and writes them to:
+//!
+//! - the terminal it runs in, if stdout is one
+//! - every tty listed in `terminal.yml` next to `latest.yml`
+//! - a `sequences` file next to `latest.yml`, for new shells to `cat`
+//!
+//! ```yaml
+//! ttys:
+//!   - /dev/pts/*      # `*` in the last component matches any name
+//!   - /dev/ttys003
+//! ```
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::SkysetError,
+    export::{Exporter, Sequences},
+    io,
+    model::Skyset,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    /// Paths or `*` patterns of the ttys to recolor.
+    pub ttys: Vec<String>,
+}
+
+/// `terminal.yml` in the same directory as `config_path`.
+pub fn terminal_config_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("terminal.yml")
+}
+
+/// `sequences` in the same directory as `config_path`.
+pub fn sequences_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("sequences")
+}
+
+impl TerminalConfig {
+    /// Reads `path`; a missing file means no ttys.
+    pub fn load(path: &Path) -> Result<Self, SkysetError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(SkysetError::from_io(path.to_path_buf(), err)),
+        };
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content)
+            .map_err(|err| SkysetError::from_yaml(path.to_path_buf(), err))
+    }
+
+    /// The listed ttys with patterns expanded, sorted and without duplicates.
+    pub fn tty_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.ttys.iter().flat_map(|tty| expand(tty)).collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
+
+/// `pattern` itself, or the entries of its directory matching a `*` in the
+/// file name.
+fn expand(pattern: &str) -> Vec<PathBuf> {
+    let path = Path::new(pattern);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let Some((prefix, suffix)) = name.split_once('*') else {
+        return vec![path.to_path_buf()];
+    };
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        // The pty multiplexer lives next to the ptys but is not one.
+        .filter(|entry| entry != "ptmx")
+        .filter(|entry| {
+            entry.len() >= prefix.len() + suffix.len()
+                && entry.starts_with(prefix)
+                && entry.ends_with(suffix)
+        })
+        .map(|entry| dir.join(entry))
+        .collect()
+}
+
+/// The escape sequences for `skyset`.
+pub fn sequences(skyset: &Skyset) -> Result<String, SkysetError> {
+    Sequences.render(skyset)
+}
+
+/// Writes `sequences` to the `sequences` file next to `config_path`.
+pub fn save_sequences(config_path: &Path, sequences: &str) -> Result<PathBuf, SkysetError> {
+    let path = sequences_path(config_path);
+    io::write_atomic(&path, sequences)?;
+    Ok(path)
+}
+
+/// Writes `sequences` to an existing tty without creating anything.
+pub fn write_to_tty(tty: &Path, sequences: &str) -> Result<(), SkysetError> {
+    OpenOptions::new()
+        .write(true)
+        .open(tty)
+        .and_then(|mut file| file.write_all(sequences.as_bytes()))
+        .map_err(|err| SkysetError::from_io(tty.to_path_buf(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn terminal_expands_ttys_and_writes_sequences() {
+        let dir = TempDir::new("terminal");
+        let pts = dir.join("pts");
+        std::fs::create_dir_all(&pts).unwrap();
+        for name in ["0", "1", "ptmx"] {
+            std::fs::write(pts.join(name), "").unwrap();
+        }
+        let config = TerminalConfig {
+            ttys: vec![
+                format!("{}/*", pts.display()),
+                format!("{}/1", pts.display()),
+            ],
+        };
+        let ttys = config.tty_paths();
+
+        let sequences = sequences(&Skyset::default()).unwrap();
+        for tty in &ttys {
+            write_to_tty(tty, &sequences).unwrap();
+        }
+        let written = std::fs::read_to_string(pts.join("1")).unwrap();
+        let missing = write_to_tty(&pts.join("9"), &sequences);
+
+        assert_eq!(ttys, [pts.join("0"), pts.join("1")]);
+        assert_eq!(written, sequences);
+        assert!(sequences.starts_with("\x1b]4;0;#"));
+        assert!(sequences.contains("\x1b]11;#0E0E10\x1b\\"));
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Helpers shared by the library's unit tests, the binary's tests and the
+//! integration tests in `tests/`, which include this file by path.
+
+// Not every test crate that includes this uses every helper.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temp dir, removed when dropped so a
+/// failing assertion does not leave it behind.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `skyset-<name>-<pid>`, emptied if an earlier run left it behind.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("skyset-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -2,60 +2,255 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 
-use crate::{
-    app::App,
+use skyset::{
     color::parse_hex_rgb,
-    editor::{FieldId, field_value},
+    field::{FieldId, field_value},
     model::theme_mode_label,
 };
 
+use crate::{
+    app::App,
+    conflict::{Choice, Conflict},
+    history_pane::HistoryPane,
+    picker::ColorPicker,
+};
+
+const SLIDER_WIDTH: usize = 24;
+
 pub fn draw_ui(frame: &mut ratatui::Frame<'_>, app: &App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)])
+        .split(frame.area());
     let body = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
-        .split(frame.area());
+        .split(rows[0]);
 
     draw_preview(frame, body[0], app);
     draw_editor(frame, body[1], app);
+    draw_status(frame, rows[1], app);
+    if let Some(pane) = app.history_pane() {
+        draw_history(frame, body[1], pane);
+    }
+    if let Some(conflict) = app.conflict() {
+        draw_conflict(frame, body[1], app, conflict);
+    }
+}
+
+fn draw_history(frame: &mut ratatui::Frame<'_>, area: Rect, pane: &HistoryPane) {
+    let mut lines = vec![
+        Line::from(Span::raw(
+            "↑/↓ browse, Enter to load into the editor (s saves), Esc to close.",
+        )),
+        Line::from(Span::raw("")),
+    ];
+    if pane.entries().is_empty() {
+        lines.push(Line::from(Span::raw("No earlier versions yet.")));
+    }
+    // Keep the selection in view; each entry is one line.
+    let visible = usize::from(area.height.saturating_sub(4)).max(1);
+    let skip = pane.selected().saturating_sub(visible - 1);
+    for (idx, entry) in pane.entries().iter().enumerate().skip(skip).take(visible) {
+        let style = if idx == pane.selected() {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let marker = if idx == pane.selected() { ">" } else { " " };
+        let mut spans = vec![Span::styled(format!("{marker}{:>3} ", idx + 1), style)];
+        // Only the selected version has been read; the others show their name.
+        if idx != pane.selected() {
+            spans.push(Span::styled(format!(" {}", entry.id), style));
+            lines.push(Line::from(spans));
+            continue;
+        }
+        match pane.current() {
+            Some(skyset) => {
+                for value in [
+                    &skyset.theme.accent,
+                    &skyset.palette.primary,
+                    &skyset.palette.secondary,
+                    &skyset.palette.tertiary,
+                ]
+                .into_iter()
+                .chain(&skyset.gradients.background)
+                {
+                    if let Some(color) = parse_color(value) {
+                        spans.push(Span::styled(" ", Style::default().bg(color)));
+                    }
+                }
+                spans.push(Span::styled(format!(" {} ", entry.id), style));
+                spans.push(Span::styled(
+                    skyset.message.clone(),
+                    Style::default().add_modifier(Modifier::DIM),
+                ));
+            }
+            None => spans.push(Span::styled(
+                format!(" {} (unreadable)", entry.id),
+                Style::default().fg(Color::Red),
+            )),
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title("History");
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_conflict(frame: &mut ratatui::Frame<'_>, area: Rect, app: &App, conflict: &Conflict) {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "latest.yml changed on disk while you were editing.",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::raw("")),
+    ];
+
+    if conflict.is_merging() {
+        lines.push(Line::from(Span::raw(
+            "↑/↓ move, Space to switch side, Enter to apply, Esc to go back.",
+        )));
+        lines.push(Line::from(Span::raw("")));
+        let merged = conflict.merged(app.skyset());
+        for (idx, (field, choice)) in conflict.fields().iter().enumerate() {
+            let style = if idx == conflict.selected() {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let side = match choice {
+                Choice::Mine => "mine  ",
+                Choice::Theirs => "theirs",
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:>16}: [{side}] ", field.label()), style),
+                Span::styled(field_value(&merged, *field), style),
+            ]));
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{:>18}mine {} / theirs {}",
+                    "",
+                    field_value(app.skyset(), *field),
+                    field_value(conflict.theirs(), *field)
+                ),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+        }
+    } else {
+        lines.push(Line::from(Span::raw(format!(
+            "{} field(s) differ from your unsaved edits.",
+            conflict.fields().len()
+        ))));
+        lines.push(Line::from(Span::raw("")));
+        lines.push(Line::from(Span::raw(
+            "m  keep mine (the next save overwrites theirs)",
+        )));
+        lines.push(Line::from(Span::raw(
+            "t  take theirs (u brings your edits back)",
+        )));
+        lines.push(Line::from(Span::raw("f  merge field by field")));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title("Conflict");
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_status(frame: &mut ratatui::Frame<'_>, area: Rect, app: &App) {
+    let skyset = app.skyset();
+    let dim = Style::default().add_modifier(Modifier::DIM);
+
+    let mut file_line = vec![Span::styled(
+        app.path().display().to_string(),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if app.is_dirty() {
+        file_line.push(Span::styled(
+            " [modified]",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let updated_at = if skyset.updated_at.is_empty() {
+        "never"
+    } else {
+        &skyset.updated_at
+    };
+    file_line.push(Span::styled(" | updated ", dim));
+    file_line.push(Span::raw(updated_at.to_string()));
+    file_line.push(Span::styled(" | source_will_update ", dim));
+    file_line.push(Span::raw(skyset.source_will_update.to_string()));
+
+    let remaining = app
+        .poll_interval()
+        .saturating_sub(app.last_poll_elapsed())
+        .as_secs();
+    let refresh = if app.is_watching() {
+        format!("watching, re-read in {remaining}s")
+    } else {
+        format!("re-read in {remaining}s")
+    };
+    let (status, status_style) = if let Some(err) = app.save_error() {
+        (
+            format!("save failed: {err}"),
+            Style::default().fg(Color::Red),
+        )
+    } else if app.read_status().is_error() {
+        (app.read_status().label(), Style::default().fg(Color::Red))
+    } else {
+        (app.read_status().label(), Style::default())
+    };
+    let status_line = vec![
+        Span::raw(refresh),
+        Span::styled(" | ", dim),
+        Span::styled(status, status_style),
+    ];
+
+    let paragraph = Paragraph::new(vec![Line::from(file_line), Line::from(status_line)]);
+    frame.render_widget(paragraph, area);
 }
 
 fn draw_preview(frame: &mut ratatui::Frame<'_>, area: Rect, app: &App) {
+    // While browsing history the preview shows the selected version.
+    let browsing = app.history_pane().and_then(|pane| pane.current());
+    let skyset = browsing.unwrap_or(app.skyset());
     let title = Span::styled(
-        "Skyset Preview",
+        if browsing.is_some() {
+            "Skyset Preview (history)"
+        } else {
+            "Skyset Preview"
+        },
         Style::default().add_modifier(Modifier::BOLD),
     );
     let lines = vec![
         Line::from(title),
         Line::from(Span::raw("")),
-        Line::from(label_value("Message", &app.skyset().message)),
-        Line::from(label_value("Submessage", &app.skyset().submessage)),
+        label_value("Message", &skyset.message),
+        label_value("Submessage", &skyset.submessage),
         Line::from(Span::raw("")),
-        Line::from(label_value(
-            "Theme mode",
-            theme_mode_label(app.skyset().theme.mode),
-        )),
-        Line::from(label_with_color("Accent", &app.skyset().theme.accent)),
+        label_value("Theme mode", theme_mode_label(skyset.theme.mode)),
+        label_with_color("Accent", &skyset.theme.accent),
         Line::from(Span::raw("")),
         Line::from(Span::raw("Palette")),
-        Line::from(label_with_color("  primary", &app.skyset().palette.primary)),
-        Line::from(label_with_color(
-            "  secondary",
-            &app.skyset().palette.secondary,
-        )),
-        Line::from(label_with_color(
-            "  tertiary",
-            &app.skyset().palette.tertiary,
-        )),
+        label_with_color("  primary", &skyset.palette.primary),
+        label_with_color("  secondary", &skyset.palette.secondary),
+        label_with_color("  tertiary", &skyset.palette.tertiary),
         Line::from(Span::raw("")),
         Line::from(Span::raw("Gradients")),
-        Line::from(gradient_line(
-            "  background",
-            &app.skyset().gradients.background,
-        )),
-        Line::from(gradient_line("  hero", &app.skyset().gradients.hero)),
+        gradient_line("  background", &skyset.gradients.background),
+        gradient_line("  hero", &skyset.gradients.hero),
     ];
 
     let block = Block::default().borders(Borders::ALL).title("Preview");
@@ -96,20 +291,63 @@ fn draw_editor(frame: &mut ratatui::Frame<'_>, area: Rect, app: &App) {
         "Editor",
         Style::default().add_modifier(Modifier::BOLD),
     ))];
-    lines.push(Line::from(Span::raw(format!(
-        "Config file: {}",
-        app.path().display()
-    ))));
     lines.push(Line::from(Span::raw("Press Tab/Shift+Tab to move.")));
     lines.push(Line::from(Span::raw("Type to edit, Enter to apply.")));
+    lines.push(Line::from(Span::raw(format!(
+        "u to undo ({}), Ctrl+R to redo ({}).",
+        app.undo_stack().undo_len(),
+        app.undo_stack().redo_len()
+    ))));
+    lines.push(Line::from(Span::raw(
+        "Press p on a color field to open the picker.",
+    )));
+    lines.push(Line::from(Span::raw("Press h to browse earlier versions.")));
     lines.push(Line::from(Span::raw("")));
     lines.extend(fields);
+    if let Some(picker) = app.picker() {
+        lines.push(Line::from(Span::raw("")));
+        lines.extend(picker_lines(picker));
+    }
 
     let block = Block::default().borders(Borders::ALL).title("Editor");
     let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
     frame.render_widget(paragraph, area);
 }
 
+fn picker_lines(picker: &ColorPicker) -> Vec<Line<'static>> {
+    let (r, g, b) = picker.rgb();
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            format!("Picker: {} ", picker.field().label()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("    ", Style::default().bg(Color::Rgb(r, g, b))),
+        Span::raw(format!(" {} (was {})", picker.hex(), picker.original())),
+    ])];
+
+    for (idx, channel) in picker.channels().iter().enumerate() {
+        let value = picker.value(*channel);
+        let filled = usize::from(value) * SLIDER_WIDTH / usize::from(channel.max());
+        let style = if idx == picker.selected() {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let marker = if idx == picker.selected() { ">" } else { " " };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{marker} {} ", channel.label()), style),
+            Span::styled("█".repeat(filled), style),
+            Span::styled("░".repeat(SLIDER_WIDTH - filled), style),
+            Span::styled(format!(" {value:>3}"), style),
+        ]));
+    }
+
+    lines.push(Line::from(Span::raw(
+        "↑/↓ channel, ←/→ adjust (Shift: ×10), Enter keep, Esc cancel",
+    )));
+    lines
+}
+
 fn label_value(label: &str, value: &str) -> Line<'static> {
     Line::from(vec![
         Span::styled(
@@ -158,7 +396,7 @@ fn color_for_field(app: &App, field: FieldId) -> Option<Color> {
             .skyset()
             .gradients
             .background
-            .get(0)
+            .first()
             .and_then(|value| parse_color(value)),
         FieldId::Background2 => app
             .skyset()
@@ -176,7 +414,7 @@ fn color_for_field(app: &App, field: FieldId) -> Option<Color> {
             .skyset()
             .gradients
             .hero
-            .get(0)
+            .first()
             .and_then(|value| parse_color(value)),
         FieldId::Hero2 => app
             .skyset()
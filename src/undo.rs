@@ -0,0 +1,43 @@
+use skyset::model::Skyset;
+
+const HISTORY_LIMIT: usize = 100;
+
+/// Snapshots of the in-memory `Skyset` taken before each change.
+#[derive(Default)]
+pub struct UndoStack {
+    undo: Vec<Skyset>,
+    redo: Vec<Skyset>,
+}
+
+impl UndoStack {
+    pub fn record(&mut self, before: Skyset) {
+        if self.undo.last() == Some(&before) {
+            return;
+        }
+        self.undo.push(before);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, current: &Skyset) -> Option<Skyset> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current.clone());
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: &Skyset) -> Option<Skyset> {
+        let next = self.redo.pop()?;
+        self.undo.push(current.clone());
+        Some(next)
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::DateTime;
+
+use crate::{
+    field::normalize_hex,
+    model::{SCHEMA_VERSION, Skyset, ThemeMode},
+};
+
+/// A value that parses but does not follow `docs/schema.md`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub path: String,
+    pub message: String,
+}
+
+pub fn validate(skyset: &Skyset) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut push = |path: &str, message: String| {
+        issues.push(Issue {
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    if skyset.version != SCHEMA_VERSION {
+        push(
+            "_version",
+            format!(
+                "unsupported version {} (expected {SCHEMA_VERSION})",
+                skyset.version
+            ),
+        );
+    }
+    if skyset.origin.trim().is_empty() {
+        push("origin", "is required".to_string());
+    }
+    if skyset.updated_at.trim().is_empty() {
+        push("updated_at", "is required".to_string());
+    } else if DateTime::parse_from_rfc3339(&skyset.updated_at).is_err() {
+        push(
+            "updated_at",
+            format!("{:?} is not an RFC3339 timestamp", skyset.updated_at),
+        );
+    }
+    if skyset.theme.mode == ThemeMode::Unknown {
+        push("theme.mode", "must be dark, light, or system".to_string());
+    }
+
+    let colors = [
+        ("theme.accent", &skyset.theme.accent),
+        ("palette.primary", &skyset.palette.primary),
+        ("palette.secondary", &skyset.palette.secondary),
+        ("palette.tertiary", &skyset.palette.tertiary),
+    ];
+    for (path, value) in colors {
+        if normalize_hex(value).is_err() {
+            push(path, format!("{value:?} is not a #RRGGBB or #RGB color"));
+        }
+    }
+    for (name, stops) in [
+        ("background", &skyset.gradients.background),
+        ("hero", &skyset.gradients.hero),
+    ] {
+        for (idx, value) in stops.iter().enumerate() {
+            if normalize_hex(value).is_err() {
+                push(
+                    &format!("gradients.{name}[{idx}]"),
+                    format!("{value:?} is not a #RRGGBB or #RGB color"),
+                );
+            }
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,364 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant, SystemTime},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+use crate::{
+    diff::{FieldChange, diff},
+    error::SkysetError,
+    io,
+    model::Skyset,
+};
+
+pub const DEBOUNCE: Duration = Duration::from_millis(150);
+pub const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Fallback polling rate while the producer says it will update again soon.
+pub const ACTIVE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Fallback polling rate once the producer says it is done for now.
+pub const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+const NATIVE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Watches a single skyset file for changes.
+///
+/// The parent directory is watched rather than the file itself, so atomic
+/// replacements (write to a temp file, then rename over `latest.yml`) and
+/// editors that swap the inode are still picked up. When no native watcher
+/// is available, the file's modification time and size are polled instead.
+pub struct FileWatcher {
+    path: PathBuf,
+    backend: Backend,
+    pending_since: Option<Instant>,
+    debounce: Duration,
+}
+
+enum Backend {
+    Native {
+        _watcher: RecommendedWatcher,
+        events: Receiver<notify::Result<Event>>,
+    },
+    Poll {
+        stamp: Option<FileStamp>,
+        last_check: Instant,
+        interval: Duration,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileWatcher {
+    pub fn new(path: &Path) -> Self {
+        let backend = native_backend(path).unwrap_or_else(|| poll_backend(path));
+        Self {
+            path: path.to_path_buf(),
+            backend,
+            pending_since: None,
+            debounce: DEBOUNCE,
+        }
+    }
+
+    pub fn is_native(&self) -> bool {
+        matches!(self.backend, Backend::Native { .. })
+    }
+
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
+
+    /// Changes how often the polling fallback checks the file. Has no effect
+    /// while a native watcher is in use.
+    pub fn set_poll_interval(&mut self, value: Duration) {
+        if let Backend::Poll { interval, .. } = &mut self.backend {
+            *interval = value;
+        }
+    }
+
+    /// Drains pending notifications and returns `true` once a burst of
+    /// changes has settled for the debounce window.
+    pub fn poll(&mut self) -> bool {
+        if self.saw_change() {
+            self.pending_since = Some(Instant::now());
+        }
+
+        match self.pending_since {
+            Some(since) if since.elapsed() >= self.debounce => {
+                self.pending_since = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// How long the caller can wait before `poll` has something new to report.
+    pub fn next_check(&self) -> Duration {
+        if let Some(since) = self.pending_since {
+            return self.debounce.saturating_sub(since.elapsed());
+        }
+        match &self.backend {
+            Backend::Native { .. } => NATIVE_CHECK_INTERVAL,
+            Backend::Poll {
+                last_check,
+                interval,
+                ..
+            } => interval.saturating_sub(last_check.elapsed()),
+        }
+    }
+
+    fn saw_change(&mut self) -> bool {
+        match &mut self.backend {
+            Backend::Native { events, .. } => {
+                let mut changed = false;
+                while let Ok(event) = events.try_recv() {
+                    match event {
+                        Ok(event) => changed |= is_relevant(&event, &self.path),
+                        Err(_) => changed = true,
+                    }
+                }
+                changed
+            }
+            Backend::Poll {
+                stamp,
+                last_check,
+                interval,
+            } => {
+                if last_check.elapsed() < *interval {
+                    return false;
+                }
+                *last_check = Instant::now();
+                let current = file_stamp(&self.path);
+                if current != *stamp {
+                    *stamp = current;
+                    return true;
+                }
+                false
+            }
+        }
+    }
+}
+
+/// A typed change to the watched file.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub old: Skyset,
+    pub new: Skyset,
+    pub changed: Vec<FieldChange>,
+}
+
+/// Consumer-side watcher: reports each settled change to `latest.yml` as
+/// the old and new `Skyset` plus the list of fields that differ.
+///
+/// Rewrites that do not change any value (a `touch`, a reformat) are not
+/// reported. A file that fails to parse is reported as an error and the
+/// last good `Skyset` is kept for the next comparison. The polling fallback
+/// speeds up while `source_will_update` is true and slows down when it is
+/// false.
+///
+/// ```no_run
+/// let path = skyset::paths::default_config_path();
+/// for change in skyset::watch::Watcher::new(&path) {
+///     let change = change?;
+///     for field in &change.changed {
+///         println!("{} changed", field.path);
+///     }
+/// }
+/// # Ok::<(), skyset::SkysetError>(())
+/// ```
+pub struct Watcher {
+    path: PathBuf,
+    files: FileWatcher,
+    current: Skyset,
+}
+
+impl Watcher {
+    /// Starts watching `path`. A missing or unreadable file starts from the
+    /// defaults.
+    pub fn new(path: &Path) -> Self {
+        let current = io::load_skyset(path).unwrap_or_default();
+        let mut watcher = Self {
+            path: path.to_path_buf(),
+            files: FileWatcher::new(path),
+            current,
+        };
+        watcher.adapt_poll_interval();
+        watcher
+    }
+
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.files.set_debounce(debounce);
+        self
+    }
+
+    pub fn current(&self) -> &Skyset {
+        &self.current
+    }
+
+    /// The current `Skyset` followed by the new one after every change.
+    pub fn into_updates(self) -> impl Iterator<Item = Result<Skyset, SkysetError>> {
+        let current = self.current.clone();
+        std::iter::once(Ok(current)).chain(self.map(|change| change.map(|change| change.new)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// How long to wait before `try_next` can have something new.
+    pub fn next_check(&self) -> Duration {
+        self.files.next_check()
+    }
+
+    /// Returns a change if one has settled, without blocking.
+    pub fn try_next(&mut self) -> Option<Result<Change, SkysetError>> {
+        if !self.files.poll() {
+            return None;
+        }
+        match io::load_skyset(&self.path) {
+            Ok(new) if new == self.current => None,
+            Ok(new) => {
+                let old = std::mem::replace(&mut self.current, new.clone());
+                self.adapt_poll_interval();
+                let changed = diff(&old, &new);
+                Some(Ok(Change { old, new, changed }))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Blocks until the next change (or read error).
+    pub fn next_change(&mut self) -> Result<Change, SkysetError> {
+        loop {
+            if let Some(result) = self.try_next() {
+                return result;
+            }
+            std::thread::sleep(self.files.next_check());
+        }
+    }
+
+    /// Calls `callback` for every change until it returns `false`.
+    pub fn run<F>(mut self, mut callback: F)
+    where
+        F: FnMut(Result<Change, SkysetError>) -> bool,
+    {
+        while callback(self.next_change()) {}
+    }
+
+    fn adapt_poll_interval(&mut self) {
+        let interval = if self.current.source_will_update {
+            ACTIVE_POLL_INTERVAL
+        } else {
+            IDLE_POLL_INTERVAL
+        };
+        self.files.set_poll_interval(interval);
+    }
+}
+
+impl Iterator for Watcher {
+    type Item = Result<Change, SkysetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_change())
+    }
+}
+
+fn native_backend(path: &Path) -> Option<Backend> {
+    let parent = watch_dir(path);
+    if !parent.is_dir() {
+        return None;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })
+    .ok()?;
+    watcher.watch(&parent, RecursiveMode::NonRecursive).ok()?;
+
+    Some(Backend::Native {
+        _watcher: watcher,
+        events: rx,
+    })
+}
+
+fn poll_backend(path: &Path) -> Backend {
+    Backend::Poll {
+        stamp: file_stamp(path),
+        last_check: Instant::now(),
+        interval: FALLBACK_POLL_INTERVAL,
+    }
+}
+
+fn watch_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+fn is_relevant(event: &Event, path: &Path) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    let Some(name) = path.file_name() else {
+        return true;
+    };
+    event
+        .paths
+        .iter()
+        .any(|changed| changed.file_name() == Some(name))
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{field::FieldId, test_support::TempDir};
+
+    #[test]
+    fn watcher_sees_atomic_replace() {
+        let dir = TempDir::new("watch");
+        let path = dir.join("latest.yml");
+        io::write_skyset(&path, "message: before\n").unwrap();
+
+        let mut watcher = FileWatcher::new(&path);
+        io::write_skyset(&path, "message: after\n").unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        let mut changed = false;
+        while !changed && std::time::Instant::now() < deadline {
+            std::thread::sleep(watcher.next_check());
+            changed = watcher.poll();
+        }
+        assert!(changed);
+    }
+
+    #[test]
+    fn watcher_reports_changed_fields() {
+        let dir = TempDir::new("changes");
+        let path = dir.join("latest.yml");
+        io::write_skyset(&path, "theme:\n  accent: \"#111111\"\n").unwrap();
+
+        let mut watcher = Watcher::new(&path).with_debounce(Duration::from_millis(20));
+        io::write_skyset(&path, "theme:\n  accent: \"#222222\"\nx_new: 1\n").unwrap();
+        let change = watcher.next_change().unwrap();
+
+        assert_eq!(change.old.theme.accent, "#111111");
+        assert_eq!(change.new.theme.accent, "#222222");
+        let paths: Vec<_> = change.changed.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["theme.accent", "x_new"]);
+        assert_eq!(change.changed[0].field(), Some(FieldId::Accent));
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use skyset::{Skyset, async_io};
+use tokio_stream::StreamExt;
+
+#[path = "../src/test_support.rs"]
+mod test_support;
+
+use test_support::TempDir;
+
+#[tokio::test]
+async fn save_then_load_round_trips() {
+    let dir = TempDir::new("async-save");
+    let path = dir.join("nested/latest.yml");
+
+    let mut skyset = Skyset {
+        message: "from tokio".to_string(),
+        ..Skyset::default()
+    };
+    async_io::save(&path, &mut skyset).await.unwrap();
+    let loaded = async_io::load(&path).await.unwrap();
+    let tmp_left = path.with_extension("yml.tmp").exists();
+
+    assert_eq!(loaded, skyset);
+    assert!(!loaded.updated_at.is_empty());
+    assert!(!tmp_left);
+}
+
+#[tokio::test]
+async fn load_reports_missing_file() {
+    let dir = TempDir::new("async-missing");
+    let result = async_io::load(dir.join("latest.yml")).await;
+
+    assert!(matches!(result, Err(skyset::SkysetError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn watch_yields_current_then_updates() {
+    let dir = TempDir::new("async-watch");
+    let path = dir.join("latest.yml");
+    async_io::write(&path, "message: first\n").await.unwrap();
+
+    let mut updates = Box::pin(async_io::watch(&path));
+    let first = updates.next().await.unwrap().unwrap();
+
+    async_io::write(&path, "message: second\n").await.unwrap();
+    let second = tokio::time::timeout(Duration::from_secs(10), updates.next())
+        .await
+        .expect("no update within 10s")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(first.message, "first");
+    assert_eq!(second.message, "second");
+}
@@ -0,0 +1,83 @@
+//! Golden-file tests for the built-in exporters.
+//!
+//! Each exporter renders `golden/input.yml` and the result must match
+//! `golden/<name>.<extension>` byte for byte. After an intended change, run
+//! `SKYSET_BLESS=1 cargo test --test export` to rewrite the golden files and
+//! review the diff.
+
+use std::path::PathBuf;
+
+use skyset::{
+    export::{Css, Exporter, Registry, Roles},
+    io,
+};
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// Compares `rendered` with the golden file `name`, or rewrites it when
+/// blessing. Prints the new output on a mismatch.
+fn matches_golden(name: &str, rendered: &str) -> bool {
+    let path = golden_dir().join(name);
+    if std::env::var_os("SKYSET_BLESS").is_some() {
+        std::fs::write(&path, rendered).unwrap();
+        return true;
+    }
+    let expected =
+        std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+    if rendered != expected {
+        eprintln!("--- {}\n{rendered}", path.display());
+    }
+    rendered == expected
+}
+
+#[test]
+fn builtin_exporters_match_golden_files() {
+    let skyset = io::load_skyset(&golden_dir().join("input.yml")).unwrap();
+    let mismatched: Vec<_> = Registry::builtin()
+        .iter()
+        .filter(|exporter| {
+            let name = format!("{}.{}", exporter.name(), exporter.extension());
+            !matches_golden(&name, &exporter.render(&skyset).unwrap())
+        })
+        .map(|exporter| exporter.name().to_string())
+        .collect();
+    assert!(mismatched.is_empty(), "output changed for: {mismatched:?}");
+}
+
+#[test]
+fn css_follows_the_color_scheme_in_system_mode() {
+    let skyset = io::load_skyset(&golden_dir().join("input-system.yml")).unwrap();
+    let css = Css.render(&skyset).unwrap();
+    assert!(css.contains("@media (prefers-color-scheme: light)"));
+    assert!(matches_golden("css-system.css", &css));
+}
+
+#[test]
+fn registry_finds_aliases_and_replaces_by_name() {
+    let mut registry = Registry::builtin();
+    assert_eq!(registry.get("yml").unwrap().name(), "yaml");
+    assert!(registry.get("nope").is_none());
+
+    let count = registry.names().len();
+    registry.register(Box::new(skyset::export::Json));
+    assert_eq!(registry.names().len(), count);
+}
+
+#[test]
+fn terminal_exporters_share_the_ansi_table() {
+    let skyset = io::load_skyset(&golden_dir().join("input.yml")).unwrap();
+    let roles = Roles::new(&skyset);
+    let registry = Registry::builtin();
+    for name in ["kitty", "alacritty", "wezterm"] {
+        let rendered = registry.get(name).unwrap().render(&skyset).unwrap();
+        for color in &roles.ansi {
+            assert!(
+                rendered.contains(color.as_str()),
+                "{name} is missing {color}"
+            );
+        }
+    }
+    assert_eq!(Roles::new(&skyset), roles);
+}
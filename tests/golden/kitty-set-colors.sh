@@ -0,0 +1 @@
+kitty @ set-colors --all --configured background=#101820 foreground=#E2E7E9 selection_background=#4FC3F7 selection_foreground=#101820 cursor=#4FC3F7 cursor_text_color=#101820 color0=#1B2733 color1=#EC5177 color2=#51EC77 color3=#C5EC51 color4=#516BEC color5=#C551EC color6=#51E0EC color7=#AEB3B7 color8=#2A3A4A color9=#F288A2 color10=#88F2A2 color11=#D7F288 color12=#889AF2 color13=#D788F2 color14=#88EAF2 color15=#E2E7E9
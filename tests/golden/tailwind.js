@@ -0,0 +1,44 @@
+// Generated by `skyset export tailwind`. In tailwind.config.js:
+//   presets: [require('./skyset.js')]
+module.exports = {
+  theme: {
+    "extend": {
+      "colors": {
+        "skyset": {
+          "accent": "var(--skyset-accent)",
+          "foreground": "var(--skyset-foreground)",
+          "primary": "var(--skyset-primary)",
+          "secondary": "var(--skyset-secondary)",
+          "tertiary": "var(--skyset-tertiary)",
+          "background-1": "var(--skyset-background-1)",
+          "background-2": "var(--skyset-background-2)",
+          "background-3": "var(--skyset-background-3)",
+          "hero-1": "var(--skyset-hero-1)",
+          "hero-2": "var(--skyset-hero-2)"
+        }
+      },
+      "backgroundImage": {
+        "skyset-background": "var(--skyset-background)",
+        "skyset-hero": "var(--skyset-hero)"
+      }
+    }
+  },
+  plugins: [({ addBase }) => addBase({
+    ":root": {
+      "color-scheme": "dark",
+      "--skyset-mode": "dark",
+      "--skyset-accent": "#4FC3F7",
+      "--skyset-foreground": "#E2E7E9",
+      "--skyset-primary": "#101820",
+      "--skyset-secondary": "#1B2733",
+      "--skyset-tertiary": "#2A3A4A",
+      "--skyset-background-1": "#0A1018",
+      "--skyset-background-2": "#132030",
+      "--skyset-background-3": "#1E3048",
+      "--skyset-background": "linear-gradient(180deg, #0A1018, #132030, #1E3048)",
+      "--skyset-hero-1": "#4FC3F7",
+      "--skyset-hero-2": "#0288D1",
+      "--skyset-hero": "linear-gradient(135deg, #4FC3F7, #0288D1)"
+    }
+  })],
+};
@@ -0,0 +1,27 @@
+-- Generated by `skyset export wezterm`. In wezterm.lua:
+--   local skyset = require 'skyset'
+--   config.colors = skyset.colors
+--   config.window_background_gradient = skyset.window_background_gradient
+return {
+  colors = {
+    background = "#101820",
+    foreground = "#E2E7E9",
+    cursor_bg = "#4FC3F7",
+    cursor_border = "#4FC3F7",
+    cursor_fg = "#101820",
+    selection_bg = "#4FC3F7",
+    selection_fg = "#101820",
+    split = "#2A3A4A",
+    ansi = { "#1B2733", "#EC5177", "#51EC77", "#C5EC51", "#516BEC", "#C551EC", "#51E0EC", "#AEB3B7" },
+    brights = { "#2A3A4A", "#F288A2", "#88F2A2", "#D7F288", "#889AF2", "#D788F2", "#88EAF2", "#E2E7E9" },
+    tab_bar = {
+      background = "#1B2733",
+      active_tab = { bg_color = "#4FC3F7", fg_color = "#101820" },
+      inactive_tab = { bg_color = "#1B2733", fg_color = "#E2E7E9" },
+    },
+  },
+  window_background_gradient = {
+    orientation = "Vertical",
+    colors = { "#0A1018", "#132030", "#1E3048" },
+  },
+}
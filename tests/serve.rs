@@ -0,0 +1,130 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpStream},
+    time::Duration,
+};
+
+use skyset::{Skyset, io, serve::Server};
+
+#[path = "../src/test_support.rs"]
+mod test_support;
+
+use test_support::TempDir;
+
+fn get(addr: SocketAddr, path: &str) -> (String, String) {
+    request(
+        addr,
+        &format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n"),
+    )
+}
+
+fn request(addr: SocketAddr, head: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(head.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.to_string(), body.to_string())
+}
+
+/// Reads one SSE event and returns its `data:` payload.
+fn next_event(reader: &mut impl BufRead) -> String {
+    let mut data = String::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() && !data.is_empty() {
+            return data;
+        }
+        if let Some(payload) = line.strip_prefix("data: ") {
+            data.push_str(payload.trim_end());
+        }
+    }
+}
+
+#[test]
+fn serves_json_css_and_events() {
+    let dir = TempDir::new("serve");
+    let path = dir.join("latest.yml");
+    let mut skyset = Skyset {
+        message: "before".to_string(),
+        ..Skyset::default()
+    };
+    io::save_skyset(&path, &mut skyset).unwrap();
+
+    let server = Server::bind(&path, "127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+
+    let (head, body) = get(addr, "/theme.json");
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+    assert!(!head.contains("Access-Control-Allow-Origin"));
+    let served: Skyset = serde_json::from_str(&body).unwrap();
+    assert_eq!(served, skyset);
+
+    let (head, body) = get(addr, "/theme.css");
+    assert!(head.contains("Content-Type: text/css"));
+    assert!(body.contains(&format!("--skyset-accent: {};", skyset.theme.accent)));
+
+    let (head, _) = get(addr, "/nope");
+    assert!(head.starts_with("HTTP/1.1 404"));
+
+    let mut events = TcpStream::connect(addr).unwrap();
+    events
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    write!(
+        events,
+        "GET /events HTTP/1.1\r\nHost: localhost:{}\r\n\r\n",
+        addr.port()
+    )
+    .unwrap();
+    let mut reader = BufReader::new(events);
+    let first: Skyset = serde_json::from_str(&next_event(&mut reader)).unwrap();
+    assert_eq!(first.message, "before");
+
+    skyset.message = "after".to_string();
+    io::save_skyset(&path, &mut skyset).unwrap();
+    let second: Skyset = serde_json::from_str(&next_event(&mut reader)).unwrap();
+
+    assert_eq!(second.message, "after");
+}
+
+#[test]
+fn guards_hosts_head_size_and_connections() {
+    let dir = TempDir::new("serve-guards");
+    let path = dir.join("latest.yml");
+    io::save_skyset(&path, &mut Skyset::default()).unwrap();
+
+    let server = Server::bind(&path, "127.0.0.1:0")
+        .unwrap()
+        .with_cors("http://localhost:3000");
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+
+    let (head, _) = get(addr, "/theme.json");
+    assert!(head.contains("Access-Control-Allow-Origin: http://localhost:3000"));
+
+    let (head, _) = request(
+        addr,
+        "GET /theme.json HTTP/1.1\r\nHost: rebound.example:7477\r\n\r\n",
+    );
+    assert!(head.starts_with("HTTP/1.1 403"));
+    let (head, _) = request(addr, "GET /theme.json HTTP/1.1\r\n\r\n");
+    assert!(head.starts_with("HTTP/1.1 403"));
+
+    let padding = "x".repeat(10 * 1024);
+    let (head, _) = request(
+        addr,
+        &format!("GET /theme.json HTTP/1.1\r\nHost: {addr}\r\nX-Padding: {padding}\r\n\r\n"),
+    );
+    assert!(head.starts_with("HTTP/1.1 431"));
+
+    // Idle connections hold their slot until the read timeout.
+    let idle: Vec<_> = (0..32).map(|_| TcpStream::connect(addr).unwrap()).collect();
+    std::thread::sleep(Duration::from_millis(200));
+    let (head, _) = get(addr, "/theme.json");
+    drop(idle);
+
+    assert!(head.starts_with("HTTP/1.1 503"));
+}